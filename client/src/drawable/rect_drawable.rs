@@ -4,20 +4,23 @@ use lib::components::transform::Transform;
 
 use some_platformer_lib::types;
 
-use ggez::graphics::{rectangle, set_color, Color, DrawMode, Rect};
+use ggez::graphics::{polygon, set_color, Color, DrawMode, Point2};
 use ggez::{Context, GameResult};
 
 impl Drawable for RectDrawable {
     fn draw(&self, ctx: &mut Context, transform: &Transform) -> GameResult<()> {
         set_color(ctx, to_color(self.color))?;
-        rectangle(ctx, DrawMode::Fill, to_rect(transform.as_rect()))
+
+        // Turned with the entity
+        let corners: Vec<Point2> = transform
+            .corners()
+            .iter()
+            .map(|corner| Point2::new(corner.x, corner.y))
+            .collect();
+        polygon(ctx, DrawMode::Fill, &corners)
     }
 }
 
 fn to_color(color: types::Color) -> Color {
     Color::new(color.x, color.y, color.z, color.w)
 }
-
-fn to_rect(rect: types::Rect) -> Rect {
-    Rect::new(rect.x, rect.y, rect.z, rect.w)
-}
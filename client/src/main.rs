@@ -1,25 +1,28 @@
+#[macro_use]
+extern crate clap;
 extern crate flexi_logger;
 extern crate ggez;
 #[macro_use]
 extern crate log;
 pub extern crate some_platformer_lib;
 
-use lib::sync::codec::Lines;
 pub use some_platformer_lib as lib;
 
+use clap::{App, Arg};
 use flexi_logger::Logger;
 use ggez::graphics::{Color, DrawMode, Rect};
 use ggez::{conf, event, graphics, Context, GameResult};
+use lib::components::input::Input;
 use lib::entities::player::Player;
-use lib::Map;
+use lib::map::{self, Map};
+use std::net::SocketAddr;
 use std::{env, path};
 
+use lib::specs::Entity;
 use lib::sync::message;
 
 use ggez::event::{Keycode, Mod};
 
-use lib::tokio::io;
-
 use std::thread;
 
 use lib::futures::sync::mpsc as ampsc;
@@ -27,41 +30,100 @@ use std::sync::mpsc as smpsc;
 
 use std::time::SystemTime;
 
-use lib::tokio::net::TcpStream;
-use lib::tokio::prelude::*;
-
 mod gameworld;
 use gameworld::GameWorld;
 
 mod drawable;
 mod sys_render;
 
-/// Shorthand for the transmit half of the game2sync channel
-type ATx = ampsc::UnboundedSender<message::Client>;
+mod sync;
+use sync::{ATx, ConnectionState, SRx, SyncEvent};
 
-/// Shorthand for the receive half of the game2sync channel
-type ARx = ampsc::UnboundedReceiver<message::Client>;
+/// The client options, from the command line
+struct Options {
+    /// Address of the server to connect to
+    server: SocketAddr,
 
-/// Shorthand for the transmit half of the sync2game channel
-type STx = smpsc::Sender<message::Server>;
+    /// Name of the player, as shown to other players
+    name: String,
 
-/// Shorthand for the receive half of the sync2game channel
-type SRx = smpsc::Receiver<message::Server>;
+    /// Size of the window, in pixels
+    width: u32,
+    height: u32,
 
-type Codec = Lines<message::Client, message::Server>;
+    /// Play without connecting to a server
+    offline: bool,
+}
 
 struct MainState<'a, 'b> {
-    map: Map,
     world: GameWorld<'a, 'b>,
+    player: Entity,
     tx: ATx,
     rx: SRx,
+    connection: ConnectionState,
+}
+
+impl<'a, 'b> MainState<'a, 'b> {
+    /// Sends a message to the server, dropped if not connected
+    fn send(&mut self, msg: message::Client) {
+        if !self.connection.is_connected() {
+            debug!("not connected, dropping {:?}", msg);
+            return;
+        }
+
+        if self.tx.unbounded_send(msg).is_err() {
+            // The sync thread is gone, the state update is on its way
+            warn!("sync thread stopped, message dropped");
+        }
+    }
+
+    /// Updates the input of the local player, when a key is pressed or released
+    fn set_input(&mut self, keycode: Keycode, held: bool) {
+        let mut inputs = self.world.entity_world.write::<Input>();
+        let input = match inputs.get_mut(self.player) {
+            Some(input) => input,
+            None => return,
+        };
+
+        match keycode {
+            Keycode::Left => input.left = held,
+            Keycode::Right => input.right = held,
+            Keycode::Up => input.up = held,
+            Keycode::Down => input.down = held,
+            Keycode::Z => input.jump = held,
+            Keycode::X => input.dash = held,
+            _ => (),
+        }
+    }
 }
 
 impl<'a, 'b> ggez::event::EventHandler for MainState<'a, 'b> {
     fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
-        // Poll sync messages
-        while let Ok(msg) = self.rx.try_recv() {
-            debug!("game got message {:?}", msg);
+        // Poll sync events
+        while let Ok(event) = self.rx.try_recv() {
+            match event {
+                SyncEvent::State(state) => {
+                    info!("connection state: {:?}", state);
+                    self.connection = state;
+                }
+                SyncEvent::Message(message::Server::Shutdown { reason }) => {
+                    info!("server is shutting down: {}", reason);
+                }
+                SyncEvent::Message(message::Server::Kicked { reason }) => {
+                    warn!("kicked from the server: {}", reason);
+                }
+                SyncEvent::Message(message::Server::Announcement(text)) => {
+                    info!("[server] {}", text);
+                }
+                SyncEvent::Message(message::Server::Map(data)) => {
+                    // Spawns what the map holds (e.g. the moving platforms)
+                    self.world.set_map(Map::from(data));
+                }
+                SyncEvent::Message(message::Server::Platforms(states)) => {
+                    self.world.set_platform_states(&states);
+                }
+                SyncEvent::Message(msg) => debug!("game got message {:?}", msg),
+            }
         }
 
         self.world.update();
@@ -72,15 +134,18 @@ impl<'a, 'b> ggez::event::EventHandler for MainState<'a, 'b> {
         graphics::clear(ctx);
 
         // TODO: Create a `TileRenderer` component, handle the map elsewhere :)
-        // draw map
+        // draw map, on the same grid the collisions use
         graphics::set_color(ctx, Color::from_rgb(255, 0, 0))?;
-        for &(x, y) in self.map.elements.keys() {
-            graphics::rectangle(
-                ctx,
-                DrawMode::Fill,
-                // draw for -10 to 10 -> 40px per block
-                Rect::new((x + 10) as f32 * 40.0, (14 - y) as f32 * 40.0, 40.0, 40.0),
-            )?;
+        {
+            let map = self.world.entity_world.read_resource::<Map>();
+            for &tile in map.elements.keys() {
+                let rect = map::tile_rect(tile);
+                graphics::rectangle(
+                    ctx,
+                    DrawMode::Fill,
+                    Rect::new(rect.x, rect.y, rect.z, rect.w),
+                )?;
+            }
         }
 
         // draws the RenderSystem
@@ -94,91 +159,15 @@ impl<'a, 'b> ggez::event::EventHandler for MainState<'a, 'b> {
     fn key_down_event(&mut self, ctx: &mut Context, keycode: Keycode, _keymod: Mod, _repeat: bool) {
         match keycode {
             Keycode::Escape => ctx.quit().expect("Should never fail"),
-            Keycode::Return => self.tx.unbounded_send(message::Client::Test).unwrap(),
-            Keycode::Space => self.tx
-                .unbounded_send(message::Client::Ping(SystemTime::now()))
-                .unwrap(),
-            _ => (),
+            Keycode::Return => self.send(message::Client::Test),
+            Keycode::Space => self.send(message::Client::Ping(SystemTime::now())),
+            _ => self.set_input(keycode, true),
         }
     }
-}
-
-/// A future that processes the broadcast logic for a connection
-struct Peer {
-    /// The TCP socket wrapped with the `Lines` codec.
-    lines: Codec,
-
-    /// Send half of the message channel
-    ///
-    /// This is used to send messages to game.
-    tx: STx,
-
-    /// Receive half of the message channel
-    ///
-    /// This is used to received messages from game. When a message is received
-    /// off of this `ARx`, it will be written to the socket.
-    rx: ARx,
-}
 
-impl Peer {
-    fn new(lines: Codec, tx: STx, rx: ARx) -> Self {
-        Peer { lines, tx, rx }
-    }
-}
-
-impl Future for Peer {
-    type Item = ();
-    type Error = io::Error;
-
-    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        // Receive all messages from peers.
-
-        // Polling an `UnboundedReceiver` cannot fail, so `unwrap`
-        // here is safe.
-        while let Async::Ready(Some(v)) = self.rx.poll().unwrap() {
-            // Buffer the line. Once all lines are buffered,
-            // they will be flushed to the socket (right
-            // below).
-            self.lines.buffer(&v)?;
-        }
-
-        // Flush the write buffer to the socket
-        let _ = self.lines.poll_flush()?;
-
-        // Read new lines from the socket
-        while let Async::Ready(line) = self.lines.poll()? {
-            debug!("Received line {:?}", line);
-
-            if let Some(message) = line {
-                if let message::Server::Pong { client, server } = message {
-                    let now = SystemTime::now();
-                    let client2server =
-                        server.duration_since(client).unwrap().subsec_nanos() as f32 / 1_000_000.0;
-                    let server2client =
-                        now.duration_since(server).unwrap().subsec_nanos() as f32 / 1_000_000.0;
-                    let client2client =
-                        now.duration_since(client).unwrap().subsec_nanos() as f32 / 1_000_000.0;
-
-                    debug!("SYNC:");
-                    debug!("\t- CLIENT -> SERVER : {:0.2}ms", client2server);
-                    debug!("\t- SERVER -> CLIENT : {:0.2}ms", server2client);
-                    debug!("\t- CLIENT -> SERVER -> CLIENT : {:0.2}ms", client2client);
-                } else {
-                    self.tx.send(message).unwrap();
-                }
-            } else {
-                // EOF was reached. The remote client has disconnected.
-                // There is nothing more to do.
-                return Ok(Async::Ready(()));
-            }
-        }
-
-        // As always, it is important to not just return `NotReady`
-        // without ensuring an inner future also returned `NotReady`.
-        //
-        // We know we got a `NotReady` from either `self.rx` or
-        // `self.lines`, so the contract is respected.
-        Ok(Async::NotReady)
+    /// A keyboard button was released.
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: Keycode, _keymod: Mod, _repeat: bool) {
+        self.set_input(keycode, false);
     }
 }
 
@@ -187,7 +176,11 @@ fn main() {
         .start()
         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e));
 
-    let c = conf::Conf::new();
+    let options = parse_options();
+
+    let mut c = conf::Conf::new();
+    c.window_mode.width = options.width;
+    c.window_mode.height = options.height;
     let ctx = &mut Context::load_from_conf("some_platformer", "gobanos", c).unwrap();
 
     // We add the CARGO_MANIFEST_DIR/resources do the filesystems paths so
@@ -202,7 +195,7 @@ fn main() {
 
     let mut game_world: GameWorld = GameWorld::new();
 
-    game_world.add_game_entity(Player::default());
+    let player = game_world.add_game_entity(Player::default()).entity();
 
     // sync to game uses sync channel
     let (sync_sender, game_receiver) = smpsc::channel();
@@ -210,41 +203,77 @@ fn main() {
     // game to sync uses async channel
     let (game_sender, sync_receiver) = ampsc::unbounded();
 
-    thread::spawn(move || sync(sync_sender, sync_receiver));
+    let connection = if options.offline {
+        info!("running offline");
+        ConnectionState::Offline
+    } else {
+        // Introduce ourselves, the message is sent as soon as the connection is up
+        game_sender
+            .unbounded_send(message::Client::Hello { name: options.name })
+            .expect("sync channel closed");
+
+        let server = options.server;
+        thread::spawn(move || sync::sync(server, sync_sender, sync_receiver));
+        ConnectionState::Connecting
+    };
 
     let state = &mut MainState {
-        map: lib::Map::default(),
         world: game_world,
+        player,
         tx: game_sender,
         rx: game_receiver,
+        connection,
     };
 
     event::run(ctx, state).unwrap();
 }
 
-fn sync(sender: STx, receiver: ARx) {
-    let addr = "127.0.0.1:3000".parse().unwrap();
-
-    let stream = TcpStream::connect(&addr).then(|stream| {
-        match stream {
-            Ok(socket) => process(socket, sender, receiver),
-            Err(err) => error!("failed to connect to server: {:?}", err),
-        }
-        Ok(())
-    });
-
-    lib::tokio::run(stream);
-}
-
-fn process(socket: TcpStream, tx: STx, rx: ARx) {
-    // Wrap the socket with the `Lines` codec that we wrote above
-    let lines = Codec::new(socket);
-
-    let connection = Peer::new(lines, tx, rx).map_err(|err| {
-        error!("failed to read line: {:?}", err);
-        ()
-    });
-
-    // Spawn the task
-    lib::tokio::spawn(connection);
+/// Parses the command line options, exits on invalid values
+fn parse_options() -> Options {
+    let matches = App::new("some_platformer")
+        .version(crate_version!())
+        .arg(
+            Arg::with_name("server")
+                .short("s")
+                .long("server")
+                .value_name("ADDR")
+                .default_value("127.0.0.1:3000")
+                .help("Address of the server to connect to"),
+        )
+        .arg(
+            Arg::with_name("name")
+                .short("n")
+                .long("name")
+                .value_name("NAME")
+                .default_value("player")
+                .help("Name of the player"),
+        )
+        .arg(
+            Arg::with_name("width")
+                .long("width")
+                .value_name("PX")
+                .default_value("800")
+                .help("Width of the window"),
+        )
+        .arg(
+            Arg::with_name("height")
+                .long("height")
+                .value_name("PX")
+                .default_value("600")
+                .help("Height of the window"),
+        )
+        .arg(
+            Arg::with_name("offline")
+                .long("offline")
+                .help("Play without connecting to a server"),
+        )
+        .get_matches();
+
+    Options {
+        server: value_t!(matches, "server", SocketAddr).unwrap_or_else(|e| e.exit()),
+        name: matches.value_of("name").unwrap().to_string(),
+        width: value_t!(matches, "width", u32).unwrap_or_else(|e| e.exit()),
+        height: value_t!(matches, "height", u32).unwrap_or_else(|e| e.exit()),
+        offline: matches.is_present("offline"),
+    }
 }
@@ -0,0 +1,194 @@
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+use lib::futures::sync::mpsc as ampsc;
+use lib::sync::codec::Lines;
+use lib::sync::message;
+use lib::tokio::io;
+use lib::tokio::net::TcpStream;
+use lib::tokio::prelude::*;
+
+use std::sync::mpsc as smpsc;
+
+/// Shorthand for the transmit half of the game2sync channel
+pub type ATx = ampsc::UnboundedSender<message::Client>;
+
+/// Shorthand for the receive half of the game2sync channel
+pub type ARx = ampsc::UnboundedReceiver<message::Client>;
+
+/// Shorthand for the transmit half of the sync2game channel
+pub type STx = smpsc::Sender<SyncEvent>;
+
+/// Shorthand for the receive half of the sync2game channel
+pub type SRx = smpsc::Receiver<SyncEvent>;
+
+type Codec = Lines<message::Client, message::Server>;
+
+/// The state of the connection to the server
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionState {
+    /// The client runs without server
+    Offline,
+
+    /// Waiting for the server to accept the connection
+    Connecting,
+
+    /// Connected to the server
+    Connected,
+
+    /// The connection could not be established
+    Failed(String),
+
+    /// The server closed the connection, or the connection was lost
+    Disconnected,
+}
+
+impl ConnectionState {
+    /// Messages can only be sent to the server when connected
+    pub fn is_connected(&self) -> bool {
+        *self == ConnectionState::Connected
+    }
+}
+
+/// An event sent from the sync thread to the game
+#[derive(Debug)]
+pub enum SyncEvent {
+    /// The connection state changed
+    State(ConnectionState),
+
+    /// The server sent a message
+    Message(message::Server),
+}
+
+/// A future that processes the broadcast logic for a connection
+struct Peer {
+    /// The TCP socket wrapped with the `Lines` codec.
+    lines: Codec,
+
+    /// Send half of the message channel
+    ///
+    /// This is used to send messages to game.
+    tx: STx,
+
+    /// Receive half of the message channel
+    ///
+    /// This is used to received messages from game. When a message is received
+    /// off of this `ARx`, it will be written to the socket.
+    rx: ARx,
+}
+
+impl Peer {
+    fn new(lines: Codec, tx: STx, rx: ARx) -> Self {
+        Peer { lines, tx, rx }
+    }
+}
+
+impl Future for Peer {
+    type Item = ();
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        // Receive all messages from peers.
+
+        // Polling an `UnboundedReceiver` cannot fail, so `unwrap`
+        // here is safe.
+        while let Async::Ready(Some(v)) = self.rx.poll().unwrap() {
+            // Buffer the line. Once all lines are buffered,
+            // they will be flushed to the socket (right
+            // below).
+            self.lines.buffer(&v)?;
+        }
+
+        // Flush the write buffer to the socket
+        let _ = self.lines.poll_flush()?;
+
+        // Read new lines from the socket
+        while let Async::Ready(line) = self.lines.poll()? {
+            debug!("Received line {:?}", line);
+
+            if let Some(message) = line {
+                match message {
+                    message::Server::Pong { client, server } => {
+                        let now = SystemTime::now();
+                        let client2server = server.duration_since(client).unwrap().subsec_nanos()
+                            as f32
+                            / 1_000_000.0;
+                        let server2client =
+                            now.duration_since(server).unwrap().subsec_nanos() as f32 / 1_000_000.0;
+                        let client2client =
+                            now.duration_since(client).unwrap().subsec_nanos() as f32 / 1_000_000.0;
+
+                        debug!("SYNC:");
+                        debug!("\t- CLIENT -> SERVER : {:0.2}ms", client2server);
+                        debug!("\t- SERVER -> CLIENT : {:0.2}ms", server2client);
+                        debug!("\t- CLIENT -> SERVER -> CLIENT : {:0.2}ms", client2client);
+                    }
+                    // The server measures the RTT, answer right away
+                    message::Server::Ping(t) => {
+                        self.lines.buffer(&message::Client::Pong(t))?;
+                    }
+                    message => {
+                        // The game may have quit, nothing to do then
+                        let _ = self.tx.send(SyncEvent::Message(message));
+                    }
+                }
+            } else {
+                // EOF was reached. The remote client has disconnected.
+                // There is nothing more to do.
+                return Ok(Async::Ready(()));
+            }
+        }
+
+        // Flush the answers to the server pings
+        let _ = self.lines.poll_flush()?;
+
+        // As always, it is important to not just return `NotReady`
+        // without ensuring an inner future also returned `NotReady`.
+        //
+        // We know we got a `NotReady` from either `self.rx` or
+        // `self.lines`, so the contract is respected.
+        Ok(Async::NotReady)
+    }
+}
+
+/// Connects to the server at `addr`, and forwards messages until disconnection
+///
+/// Blocks the current thread, the connection state is reported through `sender`.
+pub fn sync(addr: SocketAddr, sender: STx, receiver: ARx) {
+    let _ = sender.send(SyncEvent::State(ConnectionState::Connecting));
+
+    let stream = TcpStream::connect(&addr).then(move |stream| {
+        match stream {
+            Ok(socket) => {
+                info!("connected to server {}", addr);
+                let _ = sender.send(SyncEvent::State(ConnectionState::Connected));
+                process(socket, sender, receiver);
+            }
+            Err(err) => {
+                error!("failed to connect to server: {:?}", err);
+                let _ = sender.send(SyncEvent::State(ConnectionState::Failed(err.to_string())));
+            }
+        }
+        Ok(())
+    });
+
+    lib::tokio::run(stream);
+}
+
+fn process(socket: TcpStream, tx: STx, rx: ARx) {
+    // Wrap the socket with the `Lines` codec that we wrote above
+    let lines = Codec::new(socket);
+
+    let connection = Peer::new(lines, tx.clone(), rx).then(move |result| {
+        if let Err(err) = result {
+            error!("failed to read line: {:?}", err);
+        }
+
+        info!("disconnected from server");
+        let _ = tx.send(SyncEvent::State(ConnectionState::Disconnected));
+        Ok(())
+    });
+
+    // Spawn the task
+    lib::tokio::spawn(connection);
+}
@@ -3,9 +3,11 @@ use lib::components::transform::Transform;
 use lib::specs::{Join, ReadStorage, System};
 
 use drawable::Drawable;
+use ggez::graphics::{get_screen_coordinates, Rect};
 use ggez::Context;
 
-// The SysRender system draws all the RectDrawables with their Transforms on the screen
+// The SysRender system draws all the RectDrawables with their Transforms on the screen,
+// skipping the ones whose bounds are off screen
 pub struct SysRender<'c> {
     ctx: &'c mut Context,
 }
@@ -21,8 +23,15 @@ impl<'a, 'c> System<'a> for SysRender<'c> {
 
     // Called when the system has to be executed
     fn run(&mut self, (transform, rect_drawable): Self::SystemData) {
+        let screen = get_screen_coordinates(self.ctx);
+
         // Displays all the rect_drawable
         for (trans, r_draw) in (&transform, &rect_drawable).join() {
+            let bounds = trans.aabb();
+            if !screen.overlaps(&Rect::new(bounds.x, bounds.y, bounds.z, bounds.w)) {
+                continue;
+            }
+
             // TODO: Actually display the drawable to the context
             r_draw.draw(self.ctx, trans).unwrap();
         }
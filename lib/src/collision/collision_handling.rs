@@ -1,14 +1,18 @@
-use ncollide::world::{CollisionGroups, CollisionWorld2};
+use collision::layers::{CollisionLayer, InteractionMatrix};
+use components::collider::Collider;
+use components::transform::Transform;
+use nalgebra::Vector2;
+use ncollide::shape::{Cuboid, ShapeHandle};
+use ncollide::world::{CollisionGroups, CollisionWorld2, GeometricQueryType};
+use specs::{EntitiesRes, Entity};
 use std::collections::HashMap;
 
-#[derive(PartialEq, Eq, Hash)]
-pub enum CollisionLayer {
-    Normal,
-}
-
 pub struct CollisionHandler {
-    pub world: CollisionWorld2<f32, ()>,
+    // Each collision object holds the entity it belongs to
+    pub world: CollisionWorld2<f32, Entity>,
     pub collision_groups: HashMap<CollisionLayer, CollisionGroups>,
+    // How the layers interact with each other
+    interactions: InteractionMatrix,
 }
 
 impl CollisionHandler {
@@ -18,24 +22,100 @@ impl CollisionHandler {
         CollisionHandler {
             world: CollisionWorld2::new(0.02),
             collision_groups,
+            interactions: InteractionMatrix::default(),
         }
     }
 
+    /// The interactions between the collision layers
+    pub fn interactions(&self) -> &InteractionMatrix {
+        &self.interactions
+    }
+
+    /// Replaces the interactions between the collision layers
+    ///
+    /// Only the colliders added afterwards are affected.
+    pub fn set_interactions(&mut self, interactions: InteractionMatrix) {
+        self.interactions = interactions;
+        self.collision_groups.clear();
+    }
+
     pub fn get_collision_group(&mut self, layer: CollisionLayer) -> CollisionGroups {
-        let mut must_include: bool = false;
-        let collision_group = match self.collision_groups.get(&layer) {
-            Some(group) => *group,
-            None => {
-                must_include = true;
-                CollisionGroups::new()
-            }
+        let interactions = &self.interactions;
+
+        *self.collision_groups.entry(layer).or_insert_with(|| {
+            let mut group = CollisionGroups::new();
+            group.set_membership(&[layer.group_id()]);
+
+            // Only the layers it interacts with are checked against it
+            let whitelist: Vec<usize> = CollisionLayer::ALL
+                .iter()
+                .filter(|&&other| interactions.generates_events(layer, other))
+                .map(|other| other.group_id())
+                .collect();
+            group.set_whitelist(&whitelist);
+
+            group
+        })
+    }
+
+    /// Registers a cuboid collision object, sized and placed from the entity's transform
+    pub fn add_cuboid(
+        &mut self,
+        entity: Entity,
+        transform: &Transform,
+        layer: CollisionLayer,
+    ) -> Collider {
+        let half_extents = Vector2::new(transform.size.x / 2., transform.size.y / 2.);
+        let shape = ShapeHandle::new(Cuboid::new(half_extents));
+        let collision_group = self.get_collision_group(layer);
+
+        // Colliders that never block anything only need to know when they overlap
+        let query_type = if self.interactions.is_solid(layer) {
+            GeometricQueryType::Contacts(0., 0.)
+        } else {
+            GeometricQueryType::Proximity(0.)
         };
 
-        if must_include {
-            self.collision_groups.insert(layer, collision_group);
+        let handle = self.world.add(
+            transform.isometry,
+            shape,
+            collision_group,
+            query_type,
+            entity,
+        );
+
+        Collider::new(handle, layer)
+    }
+
+    /// Removes the collision objects of the entities that have been deleted
+    ///
+    /// Returns the pairs of entities that were touching, at least one of them being deleted:
+    /// ncollide doesn't report them as stopped touching once removed.
+    pub fn remove_dead(&mut self, entities: &EntitiesRes) -> Vec<(Entity, Entity)> {
+        let dead: Vec<_> = self
+            .world
+            .collision_objects()
+            .filter(|object| !entities.is_alive(*object.data()))
+            .map(|object| object.handle())
+            .collect();
+
+        if dead.is_empty() {
+            return Vec::new();
         }
 
-        collision_group
+        let touching = self
+            .world
+            .contact_pairs()
+            .filter(|&(a, b, contacts)| {
+                contacts.num_contacts() > 0
+                    && (!entities.is_alive(*a.data()) || !entities.is_alive(*b.data()))
+            })
+            .map(|(a, b, _)| (*a.data(), *b.data()))
+            .collect();
+
+        self.world.remove(&dead);
+
+        touching
     }
 }
 
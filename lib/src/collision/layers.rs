@@ -0,0 +1,141 @@
+use self::CollisionLayer::{Body, Enemy, Pickup, Player, Projectile, Terrain, Trigger};
+use std::collections::HashMap;
+
+/// The layers a collider can be part of, deciding what it interacts with
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CollisionLayer {
+    Player,
+    Terrain,
+    Enemy,
+    Projectile,
+    Trigger,
+    Pickup,
+    /// Dynamic bodies, like crates, pushed by what touches them
+    Body,
+}
+
+impl CollisionLayer {
+    /// All the layers
+    pub const ALL: [CollisionLayer; 7] = [
+        CollisionLayer::Player,
+        CollisionLayer::Terrain,
+        CollisionLayer::Enemy,
+        CollisionLayer::Projectile,
+        CollisionLayer::Trigger,
+        CollisionLayer::Pickup,
+        CollisionLayer::Body,
+    ];
+
+    /// The ncollide group the layer's members are part of
+    pub fn group_id(self) -> usize {
+        match self {
+            CollisionLayer::Player => 0,
+            CollisionLayer::Terrain => 1,
+            CollisionLayer::Enemy => 2,
+            CollisionLayer::Projectile => 3,
+            CollisionLayer::Trigger => 4,
+            CollisionLayer::Pickup => 5,
+            CollisionLayer::Body => 6,
+        }
+    }
+}
+
+/// How the colliders of two layers interact
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Interaction {
+    /// The colliders ignore each other
+    None,
+    /// The colliders go through each other, but their contacts are reported
+    Events,
+    /// The colliders block each other, and their contacts are reported
+    Collide,
+}
+
+/// The interactions between the layers, used unless configured otherwise
+pub const DEFAULT_INTERACTIONS: &[(CollisionLayer, CollisionLayer, Interaction)] = &[
+    (Player, Terrain, Interaction::Collide),
+    (Player, Enemy, Interaction::Events),
+    (Player, Projectile, Interaction::Events),
+    (Player, Trigger, Interaction::Events),
+    (Player, Pickup, Interaction::Events),
+    (Enemy, Terrain, Interaction::Collide),
+    (Enemy, Projectile, Interaction::Events),
+    (Enemy, Trigger, Interaction::Events),
+    (Projectile, Terrain, Interaction::Events),
+    (Pickup, Terrain, Interaction::Collide),
+    (Body, Terrain, Interaction::Collide),
+    (Body, Player, Interaction::Collide),
+    (Body, Enemy, Interaction::Collide),
+    (Body, Body, Interaction::Collide),
+    (Body, Projectile, Interaction::Events),
+    (Body, Trigger, Interaction::Events),
+];
+
+/// A symmetric matrix telling how each pair of layers interact
+///
+/// Pairs missing from the matrix don't interact.
+#[derive(Clone, Debug)]
+pub struct InteractionMatrix {
+    interactions: HashMap<(CollisionLayer, CollisionLayer), Interaction>,
+}
+
+impl InteractionMatrix {
+    /// Creates a matrix where no layer interacts with any other
+    pub fn new() -> Self {
+        InteractionMatrix {
+            interactions: HashMap::new(),
+        }
+    }
+
+    /// Creates a matrix from a list of interactions
+    pub fn from_table(table: &[(CollisionLayer, CollisionLayer, Interaction)]) -> Self {
+        table
+            .iter()
+            .fold(InteractionMatrix::new(), |matrix, &(a, b, interaction)| {
+                matrix.with(a, b, interaction)
+            })
+    }
+
+    /// Sets how the layers `a` and `b` interact
+    pub fn with(mut self, a: CollisionLayer, b: CollisionLayer, interaction: Interaction) -> Self {
+        self.set(a, b, interaction);
+        self
+    }
+
+    /// Sets how the layers `a` and `b` interact
+    pub fn set(&mut self, a: CollisionLayer, b: CollisionLayer, interaction: Interaction) {
+        self.interactions.insert((a, b), interaction);
+        self.interactions.insert((b, a), interaction);
+    }
+
+    /// How the layers `a` and `b` interact
+    pub fn get(&self, a: CollisionLayer, b: CollisionLayer) -> Interaction {
+        self.interactions
+            .get(&(a, b))
+            .cloned()
+            .unwrap_or(Interaction::None)
+    }
+
+    /// Whether the colliders of the layers `a` and `b` block each other
+    pub fn collides(&self, a: CollisionLayer, b: CollisionLayer) -> bool {
+        self.get(a, b) == Interaction::Collide
+    }
+
+    /// Whether the contacts between the layers `a` and `b` are reported
+    pub fn generates_events(&self, a: CollisionLayer, b: CollisionLayer) -> bool {
+        self.get(a, b) != Interaction::None
+    }
+
+    /// Whether the colliders of the layer block any other collider
+    pub fn is_solid(&self, layer: CollisionLayer) -> bool {
+        CollisionLayer::ALL
+            .iter()
+            .any(|&other| self.collides(layer, other))
+    }
+}
+
+impl Default for InteractionMatrix {
+    fn default() -> Self {
+        InteractionMatrix::from_table(DEFAULT_INTERACTIONS)
+    }
+}
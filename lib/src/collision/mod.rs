@@ -1 +1,7 @@
 pub mod collision_handling;
+pub mod layers;
+pub mod oriented;
+pub mod queries;
+pub mod resolution;
+pub mod statics;
+pub mod tiles;
@@ -0,0 +1,181 @@
+use nalgebra::{Point2, Vector2};
+use std::f32;
+use types::Rect;
+
+// How close to a quarter turn a rectangle must be to have its sides along the axes
+const ALIGNMENT_TOLERANCE: f32 = 1e-6;
+
+// How deep two rectangles can overlap and still be considered touching, in pixels
+const CONTACT_TOLERANCE: f32 = 0.01;
+
+// Below this motion along an axis (in pixels), a rectangle is considered not moving along it
+// (e.g. when sliding along a side)
+const MIN_MOTION: f32 = 1e-6;
+
+/// A rectangle turned around its center (e.g. the collider of a rotated entity)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrientedRect {
+    pub center: Vector2<f32>,
+    /// Half its width and height, before turning it
+    pub half_extents: Vector2<f32>,
+    /// How much it is turned, in radians (clockwise on screen, as the y axis points down)
+    pub angle: f32,
+}
+
+impl OrientedRect {
+    pub fn new(center: Vector2<f32>, half_extents: Vector2<f32>, angle: f32) -> Self {
+        OrientedRect {
+            center,
+            half_extents,
+            angle,
+        }
+    }
+
+    /// The given rectangle, not turned
+    pub fn from_rect(rect: &Rect) -> Self {
+        OrientedRect::new(
+            Vector2::new(rect.x + rect.z / 2., rect.y + rect.w / 2.),
+            Vector2::new(rect.z / 2., rect.w / 2.),
+            0.,
+        )
+    }
+
+    /// Whether its sides are along the axes (it is turned by a multiple of a quarter turn)
+    pub fn is_axis_aligned(&self) -> bool {
+        let (sin, cos) = self.angle.sin_cos();
+        sin.abs() < ALIGNMENT_TOLERANCE || cos.abs() < ALIGNMENT_TOLERANCE
+    }
+
+    /// The directions of its sides: along its width, then along its height
+    pub fn axes(&self) -> [Vector2<f32>; 2] {
+        let (sin, cos) = self.angle.sin_cos();
+        [Vector2::new(cos, sin), Vector2::new(-sin, cos)]
+    }
+
+    /// Its corners, clockwise from the one which is the top left before turning it
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        let axes = self.axes();
+        let width = axes[0] * self.half_extents.x;
+        let height = axes[1] * self.half_extents.y;
+        let center = Point2::new(self.center.x, self.center.y);
+
+        [
+            center - width - height,
+            center + width - height,
+            center + width + height,
+            center - width + height,
+        ]
+    }
+
+    /// The smallest axis-aligned rectangle containing it
+    pub fn bounds(&self) -> Rect {
+        let extent_x = self.radius(Vector2::new(1., 0.));
+        let extent_y = self.radius(Vector2::new(0., 1.));
+
+        Rect::new(
+            self.center.x - extent_x,
+            self.center.y - extent_y,
+            extent_x * 2.,
+            extent_y * 2.,
+        )
+    }
+
+    // How far it extends from its center along the given direction
+    fn radius(&self, direction: Vector2<f32>) -> f32 {
+        let axes = self.axes();
+        self.half_extents.x * axes[0].dot(&direction).abs()
+            + self.half_extents.y * axes[1].dot(&direction).abs()
+    }
+
+    // The interval it covers along the given direction
+    fn project(&self, direction: Vector2<f32>) -> (f32, f32) {
+        let center = self.center.dot(&direction);
+        let radius = self.radius(direction);
+
+        (center - radius, center + radius)
+    }
+}
+
+// The directions along which two rectangles can be told apart: the sides of both
+fn separating_axes(a: &OrientedRect, b: &OrientedRect) -> [Vector2<f32>; 4] {
+    let (a_axes, b_axes) = (a.axes(), b.axes());
+    [a_axes[0], a_axes[1], b_axes[0], b_axes[1]]
+}
+
+/// Computes the smallest translation that pushes `a` out of `b`, like
+/// `resolution::penetration` but for turned rectangles
+///
+/// Returns `None` if the rectangles don't overlap (touching is not overlapping).
+pub fn penetration(a: &OrientedRect, b: &OrientedRect) -> Option<Vector2<f32>> {
+    let mut smallest: Option<Vector2<f32>> = None;
+
+    for &axis in &separating_axes(a, b) {
+        let (a_min, a_max) = a.project(axis);
+        let (b_min, b_max) = b.project(axis);
+        if a_max <= b_min || b_max <= a_min {
+            return None;
+        }
+
+        // Away from `b`, on the side of its center `a` is
+        let push = if a.center.dot(&axis) < b.center.dot(&axis) {
+            axis * (b_min - a_max)
+        } else {
+            axis * (b_max - a_min)
+        };
+        if smallest.map_or(true, |smallest| push.norm() < smallest.norm()) {
+            smallest = Some(push);
+        }
+    }
+
+    smallest
+}
+
+/// Computes when `a`, moving by `motion`, first touches `b`, like
+/// `resolution::time_of_impact` but for turned rectangles
+///
+/// Returns the fraction of the motion done at the time of impact (between 0 and 1),
+/// and the normal of the touched side of `b`. Returns `None` if they don't touch during
+/// the motion, or if they already overlap by more than touching.
+pub fn time_of_impact(
+    a: &OrientedRect,
+    motion: Vector2<f32>,
+    b: &OrientedRect,
+) -> Option<(f32, Vector2<f32>)> {
+    let mut entry = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vector2::new(0., 0.);
+    let mut entry_speed = 0.;
+
+    for &axis in &separating_axes(a, b) {
+        let (a_min, a_max) = a.project(axis);
+        let (b_min, b_max) = b.project(axis);
+        let speed = motion.dot(&axis);
+
+        let (axis_entry, axis_exit) = if speed > MIN_MOTION {
+            ((b_min - a_max) / speed, (b_max - a_min) / speed)
+        } else if speed < -MIN_MOTION {
+            ((b_max - a_min) / speed, (b_min - a_max) / speed)
+        } else if a_min < b_max && b_min < a_max {
+            // Not moving along this axis, but always overlapping on it
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            return None;
+        };
+
+        // The side is the one of the axis reached last
+        if axis_entry > entry {
+            entry = axis_entry;
+            normal = -axis * speed.signum();
+            entry_speed = speed.abs();
+        }
+        exit = exit.min(axis_exit);
+    }
+
+    // Already overlapping, unless only by touching
+    let overlapping = entry == f32::NEG_INFINITY || -entry * entry_speed > CONTACT_TOLERANCE;
+    if entry > exit || entry > 1. || overlapping {
+        return None;
+    }
+
+    Some((entry.max(0.), normal))
+}
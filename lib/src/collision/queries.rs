@@ -0,0 +1,223 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use nalgebra::{Isometry2, Point2, Vector2};
+use ncollide::bounding_volume::{self, BoundingVolume, AABB};
+use ncollide::query::{self, Ray};
+use ncollide::shape::Cuboid;
+use ncollide::world::{CollisionGroups, CollisionObject2};
+use specs::Entity;
+
+// How close the shapes have to be, at the time of impact, to compute the contact
+const CONTACT_PREDICTION: f32 = 0.01;
+
+/// Selects the colliders a query can hit
+///
+/// Only the colliders of the given layers are considered. Colliders of a layer interacting
+/// with no other layer can't be hit.
+#[derive(Clone, Copy, Debug)]
+pub struct QueryFilter<'a> {
+    pub layers: &'a [CollisionLayer],
+    pub exclude: Option<Entity>,
+}
+
+impl<'a> QueryFilter<'a> {
+    /// A filter accepting the colliders of the given layers
+    pub fn layers(layers: &'a [CollisionLayer]) -> Self {
+        QueryFilter {
+            layers,
+            exclude: None,
+        }
+    }
+
+    /// Ignores the collider of the given entity (e.g. the one casting a ray from its center)
+    pub fn excluding(mut self, entity: Entity) -> Self {
+        self.exclude = Some(entity);
+        self
+    }
+
+    // The collision groups matching the filtered layers
+    fn groups(&self) -> CollisionGroups {
+        let mut groups = CollisionGroups::new();
+        let whitelist: Vec<usize> = self.layers.iter().map(|layer| layer.group_id()).collect();
+        groups.set_whitelist(&whitelist);
+
+        groups
+    }
+
+    // Whether the collision object is accepted
+    fn accepts(&self, object: &CollisionObject2<f32, Entity>) -> bool {
+        self.exclude != Some(*object.data())
+    }
+}
+
+/// A collider hit by a ray
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// The entity owning the collider
+    pub entity: Entity,
+    /// Where the ray hit the collider
+    pub point: Point2<f32>,
+    /// The normal of the collider's surface, at the hit point
+    pub normal: Vector2<f32>,
+    /// The distance traveled by the ray, in pixels
+    pub distance: f32,
+}
+
+/// A collider hit by a moving shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeHit {
+    /// The entity owning the collider
+    pub entity: Entity,
+    /// Where the shape touched the collider
+    pub point: Point2<f32>,
+    /// The normal of the collider's surface, at the contact point
+    pub normal: Vector2<f32>,
+    /// The fraction of the motion done when touching the collider (between 0 and 1)
+    pub time: f32,
+}
+
+impl CollisionHandler {
+    /// Casts a ray, returning the closest collider hit within `max_distance`
+    pub fn raycast(
+        &self,
+        origin: Point2<f32>,
+        direction: Vector2<f32>,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Option<RayHit> {
+        self.raycast_all(origin, direction, max_distance, filter)
+            .into_iter()
+            .next()
+    }
+
+    /// Casts a ray, returning all the colliders hit within `max_distance`, closest first
+    pub fn raycast_all(
+        &self,
+        origin: Point2<f32>,
+        direction: Vector2<f32>,
+        max_distance: f32,
+        filter: QueryFilter,
+    ) -> Vec<RayHit> {
+        if direction.norm() == 0. {
+            return Vec::new();
+        }
+
+        let direction = direction.normalize();
+        let ray = Ray::new(origin, direction);
+        let groups = filter.groups();
+
+        let mut hits: Vec<RayHit> = self
+            .world
+            .interferences_with_ray(&ray, &groups)
+            .filter(|&(object, ref intersection)| {
+                filter.accepts(object) && intersection.toi <= max_distance
+            })
+            .map(|(object, intersection)| RayHit {
+                entity: *object.data(),
+                point: origin + direction * intersection.toi,
+                normal: intersection.normal,
+                distance: intersection.toi,
+            })
+            .collect();
+
+        hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+        hits
+    }
+
+    /// Moves a box of the given size, centered on `position`, by `motion`, returning
+    /// the first collider it touches
+    ///
+    /// A box not moving touches nothing, use `overlapping` to find what it overlaps.
+    pub fn shape_cast(
+        &self,
+        size: Vector2<f32>,
+        position: Point2<f32>,
+        motion: Vector2<f32>,
+        filter: QueryFilter,
+    ) -> Option<ShapeHit> {
+        if motion.norm() == 0. {
+            return None;
+        }
+
+        let shape = Cuboid::new(size / 2.);
+        let start = Isometry2::new(position.coords, 0.);
+        let end = Isometry2::new(position.coords + motion, 0.);
+
+        // Only the colliders around the path of the shape can be touched
+        let path =
+            bounding_volume::aabb(&shape, &start).merged(&bounding_volume::aabb(&shape, &end));
+        let groups = filter.groups();
+        let still = Vector2::new(0., 0.);
+
+        let mut first: Option<(f32, &CollisionObject2<f32, Entity>)> = None;
+        for object in self.world.interferences_with_aabb(&path, &groups) {
+            if !filter.accepts(object) {
+                continue;
+            }
+
+            let time = match query::time_of_impact(
+                &start,
+                &motion,
+                &shape,
+                object.position(),
+                &still,
+                object.shape().as_ref(),
+            ) {
+                Some(time) if time <= 1. => time,
+                _ => continue,
+            };
+
+            first = match first {
+                Some(first) if first.0 <= time => Some(first),
+                _ => Some((time, object)),
+            };
+        }
+
+        first.map(|(time, object)| {
+            let position = position + motion * time;
+            let at_impact = Isometry2::new(position.coords, 0.);
+
+            let contact = query::contact(
+                &at_impact,
+                &shape,
+                object.position(),
+                object.shape().as_ref(),
+                CONTACT_PREDICTION,
+            );
+
+            let (point, normal) = match contact {
+                Some(contact) => (contact.world2, -*contact.normal),
+                // Shouldn't happen, as the shapes are touching
+                None => (position, -motion.normalize()),
+            };
+
+            ShapeHit {
+                entity: *object.data(),
+                point,
+                normal,
+                time,
+            }
+        })
+    }
+
+    /// The entities whose collider overlaps the given area
+    pub fn overlapping(
+        &self,
+        mins: Point2<f32>,
+        maxs: Point2<f32>,
+        filter: QueryFilter,
+    ) -> Vec<Entity> {
+        let area = AABB::new(mins, maxs);
+        let groups = filter.groups();
+
+        // The broad phase is not exact, check the actual bounds of the colliders
+        self.world
+            .interferences_with_aabb(&area, &groups)
+            .filter(|object| filter.accepts(object))
+            .filter(|object| {
+                bounding_volume::aabb(object.shape().as_ref(), object.position()).intersects(&area)
+            })
+            .map(|object| *object.data())
+            .collect()
+    }
+}
@@ -0,0 +1,125 @@
+use nalgebra::Vector2;
+use std::f32;
+use types::Rect;
+
+// Below this speed (in pixels per second), impacts don't bounce: what rests on something
+// stays at rest instead of bouncing endlessly
+const RESTING_SPEED: f32 = 50.;
+
+/// Area covered by both rectangles, 0 if they don't overlap
+pub fn overlap_area(a: &Rect, b: &Rect) -> f32 {
+    let (overlap_x, overlap_y) = overlap(a, b);
+
+    if overlap_x > 0. && overlap_y > 0. {
+        overlap_x * overlap_y
+    } else {
+        0.
+    }
+}
+
+/// Computes the smallest translation that pushes `a` out of `b`
+///
+/// The translation is along the axis of least penetration, and points away from `b`.
+/// Returns `None` if the rectangles don't overlap (touching is not overlapping).
+pub fn penetration(a: &Rect, b: &Rect) -> Option<Vector2<f32>> {
+    let (overlap_x, overlap_y) = overlap(a, b);
+
+    if overlap_x <= 0. || overlap_y <= 0. {
+        return None;
+    }
+
+    // Compare the centers to know on which side of `b` the rectangle `a` is
+    let a_center = Vector2::new(a.x + a.z / 2., a.y + a.w / 2.);
+    let b_center = Vector2::new(b.x + b.z / 2., b.y + b.w / 2.);
+
+    if overlap_x < overlap_y {
+        let direction = if a_center.x < b_center.x { -1. } else { 1. };
+        Some(Vector2::new(direction * overlap_x, 0.))
+    } else {
+        let direction = if a_center.y < b_center.y { -1. } else { 1. };
+        Some(Vector2::new(0., direction * overlap_y))
+    }
+}
+
+/// Computes the translation that puts `a` back on top of `b`, against `down` (normalized)
+///
+/// Used for one-way platforms, which only block what lands on them.
+/// Returns `None` if the rectangles don't overlap.
+pub fn penetration_from_above(a: &Rect, b: &Rect, down: Vector2<f32>) -> Option<Vector2<f32>> {
+    let (overlap_x, overlap_y) = overlap(a, b);
+
+    if overlap_x <= 0. || overlap_y <= 0. {
+        return None;
+    }
+
+    let depth = projection(a, down).1 - projection(b, down).0;
+    Some(-down * depth)
+}
+
+/// The nearest and farthest the rectangle reaches along the direction (normalized)
+pub fn projection(rect: &Rect, direction: Vector2<f32>) -> (f32, f32) {
+    let center = Vector2::new(rect.x + rect.z / 2., rect.y + rect.w / 2.).dot(&direction);
+    let half_extent = (direction.x.abs() * rect.z + direction.y.abs() * rect.w) / 2.;
+
+    (center - half_extent, center + half_extent)
+}
+
+/// The speed along an axis after an impact at `speed`, bouncing back by the restitution
+/// (between 0 and 1)
+pub fn bounce(speed: f32, restitution: f32) -> f32 {
+    if speed.abs() < RESTING_SPEED {
+        0.
+    } else {
+        -speed * restitution
+    }
+}
+
+/// Computes when `a`, moving by `motion`, first touches `b`
+///
+/// Returns the fraction of the motion done at the time of impact (between 0 and 1),
+/// and the normal of the touched side of `b`. Returns `None` if they don't touch during
+/// the motion, or if they already overlap (see `penetration` for that case).
+pub fn time_of_impact(a: &Rect, motion: Vector2<f32>, b: &Rect) -> Option<(f32, Vector2<f32>)> {
+    let (entry_x, exit_x) = axis_impact(a.x, a.z, motion.x, b.x, b.z)?;
+    let (entry_y, exit_y) = axis_impact(a.y, a.w, motion.y, b.y, b.w)?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+
+    if entry > exit || entry < 0. || entry > 1. {
+        return None;
+    }
+
+    // The side is the one of the axis reached last
+    let normal = if entry_x > entry_y {
+        Vector2::new(-motion.x.signum(), 0.)
+    } else {
+        Vector2::new(0., -motion.y.signum())
+    };
+
+    Some((entry, normal))
+}
+
+// When the segment [a, a + a_size], moving by `motion`, enters and exits [b, b + b_size],
+// as fractions of the motion
+fn axis_impact(a: f32, a_size: f32, motion: f32, b: f32, b_size: f32) -> Option<(f32, f32)> {
+    if motion > 0. {
+        Some(((b - (a + a_size)) / motion, (b + b_size - a) / motion))
+    } else if motion < 0. {
+        Some(((b + b_size - a) / motion, (b - (a + a_size)) / motion))
+    } else if a < b + b_size && b < a + a_size {
+        // Not moving along this axis, but always overlapping on it
+        Some((f32::NEG_INFINITY, f32::INFINITY))
+    } else {
+        None
+    }
+}
+
+// The overlap of two rectangles on each axis, negative if they are apart
+// (rectangles are stored as (x, y, width, height))
+fn overlap(a: &Rect, b: &Rect) -> (f32, f32) {
+    let overlap_x = (a.x + a.z).min(b.x + b.z) - a.x.max(b.x);
+    let overlap_y = (a.y + a.w).min(b.y + b.w) - a.y.max(b.y);
+
+    (overlap_x, overlap_y)
+}
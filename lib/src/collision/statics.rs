@@ -0,0 +1,170 @@
+use collision::layers::{CollisionLayer, InteractionMatrix};
+use collision::oriented::{self, OrientedRect};
+use collision::resolution::{projection, time_of_impact};
+use collision::tiles::Sweep;
+use components::collider::Collider;
+use components::moving::Moving;
+use components::transform::Transform;
+use nalgebra::Vector2;
+use specs::{Join, MaskedStorage, ReadStorage, Storage};
+use std::ops::Deref;
+use types::Rect;
+
+// How many colliders a single motion can be stopped by, sliding along them
+const MAX_IMPACTS: usize = 3;
+
+// How far an entity can sink into a one-way platform in a single update and still land on it
+const ONE_WAY_TOLERANCE: f32 = 0.01;
+
+/// A collider that doesn't move, as seen by the moving entities
+#[derive(Clone, Copy, Debug)]
+pub struct StaticCollider {
+    /// Its axis-aligned bounds
+    pub rect: Rect,
+    /// Its actual shape, which may be turned
+    pub shape: OrientedRect,
+    pub layer: CollisionLayer,
+    pub one_way: bool,
+}
+
+impl StaticCollider {
+    /// Collects the static colliders: the ones without a `Moving` component
+    ///
+    /// The storages can be read or written by the calling system.
+    pub fn collect<T, M>(
+        transform: &Storage<Transform, T>,
+        collider: &ReadStorage<Collider>,
+        moving: &Storage<Moving, M>,
+    ) -> Vec<StaticCollider>
+    where
+        T: Deref<Target = MaskedStorage<Transform>>,
+        M: Deref<Target = MaskedStorage<Moving>>,
+    {
+        (transform, collider, !moving)
+            .join()
+            .map(|(tr, col, _)| StaticCollider {
+                rect: tr.aabb(),
+                shape: tr.oriented_rect(),
+                layer: col.layer,
+                one_way: col.one_way,
+            })
+            .collect()
+    }
+
+    /// Whether the collider blocks a moving entity of the given layer
+    ///
+    /// One-way platforms only block the entities coming from above, unless they are dropping
+    /// through: above is against `down`, where the entity's gravity pulls it (normalized), and
+    /// its bottom reached `previous_bottom` along it before moving.
+    pub fn blocks(
+        &self,
+        interactions: &InteractionMatrix,
+        layer: CollisionLayer,
+        previous_bottom: f32,
+        down: Vector2<f32>,
+        drop_through: bool,
+    ) -> bool {
+        if !interactions.collides(layer, self.layer) {
+            return false;
+        }
+
+        let top = projection(&self.rect, down).0;
+        !self.one_way || (!drop_through && previous_bottom <= top + ONE_WAY_TOLERANCE)
+    }
+}
+
+/// Moves a rectangle of the given layer, pulled towards `down`, stopping it against the first
+/// blocking collider on its way (see `StaticCollider::blocks`)
+pub fn sweep(
+    statics: &[StaticCollider],
+    interactions: &InteractionMatrix,
+    layer: CollisionLayer,
+    rect: &Rect,
+    motion: Vector2<f32>,
+    down: Vector2<f32>,
+    drop_through: bool,
+) -> Sweep {
+    let mut rect = *rect;
+    let mut remaining = motion;
+    let mut sweep = Sweep {
+        motion: Vector2::new(0., 0.),
+        blocked_x: false,
+        blocked_y: false,
+        normal: None,
+    };
+
+    for _ in 0..MAX_IMPACTS {
+        let bottom = projection(&rect, down).1;
+
+        // Find the first collider hit
+        let mut first_impact: Option<(f32, Vector2<f32>, &StaticCollider)> = None;
+        for other in statics {
+            if !other.blocks(interactions, layer, bottom, down, drop_through) {
+                continue;
+            }
+
+            let impact = if other.shape.is_axis_aligned() {
+                time_of_impact(&rect, remaining, &other.rect)
+            } else {
+                oriented::time_of_impact(&OrientedRect::from_rect(&rect), remaining, &other.shape)
+            };
+            let impact = match impact {
+                // One-way platforms can only be hit on their top side
+                Some((_, normal)) if other.one_way && normal.dot(&down) >= 0. => continue,
+                Some((time, normal)) => (time, normal, other),
+                None => continue,
+            };
+
+            first_impact = match first_impact {
+                Some(first) if first.0 <= impact.0 => Some(first),
+                _ => Some(impact),
+            };
+        }
+
+        let (time, normal, other) = match first_impact {
+            Some(impact) => impact,
+            None => {
+                sweep.motion += remaining;
+                break;
+            }
+        };
+
+        // Move up to the collider (exactly touching it along the blocked axis, when its sides
+        // are along the axes), then slide along it
+        let aligned = other.shape.is_axis_aligned();
+        let mut moved = remaining * time;
+        if aligned {
+            let other = &other.rect;
+            if normal.x < 0. {
+                moved.x = other.x - (rect.x + rect.z);
+            } else if normal.x > 0. {
+                moved.x = other.x + other.z - rect.x;
+            } else if normal.y < 0. {
+                moved.y = other.y - (rect.y + rect.w);
+            } else {
+                moved.y = other.y + other.w - rect.y;
+            }
+        }
+
+        sweep.motion += moved;
+        rect.x += moved.x;
+        rect.y += moved.y;
+        remaining -= moved;
+
+        if !aligned {
+            // Along the turned side, whatever its direction
+            remaining -= normal * remaining.dot(&normal);
+            sweep.blocked_x = sweep.blocked_x || normal.x != 0.;
+            sweep.blocked_y = sweep.blocked_y || normal.y != 0.;
+            sweep.normal = Some(normal);
+        } else if normal.x != 0. {
+            remaining.x = 0.;
+            sweep.blocked_x = true;
+        } else {
+            remaining.y = 0.;
+            sweep.blocked_y = true;
+        }
+    }
+
+    sweep
+}
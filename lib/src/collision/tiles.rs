@@ -0,0 +1,108 @@
+use map::{cell_at, Map, TILE_SIZE};
+use nalgebra::Vector2;
+use types::Rect;
+
+// Keeps the edges of a rectangle lying exactly on a grid line out of the next cell
+const EPSILON: f32 = 0.001;
+
+/// The outcome of sweeping a rectangle through the tiles of a map
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    /// How far the rectangle can actually move
+    pub motion: Vector2<f32>,
+    /// Whether a tile stopped the motion along the x axis
+    pub blocked_x: bool,
+    /// Whether a tile stopped the motion along the y axis
+    pub blocked_y: bool,
+    /// The normal of the last turned side the rectangle slid along, if any (never for tiles)
+    pub normal: Option<Vector2<f32>>,
+}
+
+/// Moves a rectangle through the tiles of a map, stopping it against the first solid tile
+/// on its way
+///
+/// The motion is swept along the x axis first, then along the y axis. One-way tiles only stop
+/// a rectangle falling on them from above, unless `drop_through` is set.
+pub fn sweep(map: &Map, rect: &Rect, motion: Vector2<f32>, drop_through: bool) -> Sweep {
+    let (x, y, w, h) = (rect.x, rect.y, rect.z, rect.w);
+
+    // The rows covered by the rectangle, while moving along the x axis
+    let rows = (cell_at(y + EPSILON), cell_at(y + h - EPSILON));
+    let (motion_x, blocked_x) = sweep_axis(x, w, motion.x, |column| {
+        (rows.0..=rows.1).any(|row| match map.block_in_cell(column, row) {
+            Some(block) => !block.one_way,
+            None => false,
+        })
+    });
+
+    // The columns covered by the rectangle, once moved along the x axis
+    let x = x + motion_x;
+    let columns = (cell_at(x + EPSILON), cell_at(x + w - EPSILON));
+    let falling = motion.y > 0.;
+    let (motion_y, blocked_y) = sweep_axis(y, h, motion.y, |row| {
+        (columns.0..=columns.1).any(|column| match map.block_in_cell(column, row) {
+            Some(block) => !block.one_way || (falling && !drop_through),
+            None => false,
+        })
+    });
+
+    Sweep {
+        motion: Vector2::new(motion_x, motion_y),
+        blocked_x,
+        blocked_y,
+        normal: None,
+    }
+}
+
+/// Whether a rectangle overlaps a solid tile of the map (one-way tiles are not solid)
+pub fn overlaps(map: &Map, rect: &Rect) -> bool {
+    let columns = (
+        cell_at(rect.x + EPSILON),
+        cell_at(rect.x + rect.z - EPSILON),
+    );
+    let rows = (
+        cell_at(rect.y + EPSILON),
+        cell_at(rect.y + rect.w - EPSILON),
+    );
+
+    (columns.0..=columns.1).any(|column| {
+        (rows.0..=rows.1).any(|row| match map.block_in_cell(column, row) {
+            Some(block) => !block.one_way,
+            None => false,
+        })
+    })
+}
+
+// Sweeps the segment [start, start + size] by `motion`, along a single axis
+//
+// `is_solid` tells if a line of cells (a column or a row), perpendicular to the axis,
+// blocks the segment. Returns the allowed motion, and whether it was blocked.
+fn sweep_axis<F>(start: f32, size: f32, motion: f32, is_solid: F) -> (f32, bool)
+where
+    F: Fn(i32) -> bool,
+{
+    if motion > 0. {
+        // The cells ahead of the leading edge, up to where it ends
+        let edge = start + size;
+        let first = cell_at(edge - EPSILON) + 1;
+        let last = cell_at(edge + motion - EPSILON);
+
+        for cell in first..=last {
+            if is_solid(cell) {
+                return (cell as f32 * TILE_SIZE - edge, true);
+            }
+        }
+    } else if motion < 0. {
+        let edge = start;
+        let first = cell_at(edge + EPSILON) - 1;
+        let last = cell_at(edge + motion + EPSILON);
+
+        for cell in (last..=first).rev() {
+            if is_solid(cell) {
+                return ((cell + 1) as f32 * TILE_SIZE - edge, true);
+            }
+        }
+    }
+
+    (motion, false)
+}
@@ -0,0 +1,26 @@
+use specs::{Component, HashMapStorage};
+
+/// A component making the entity's trigger climbable (e.g. a ladder or vines): the characters
+/// inside can grab it, then move up and down without gravity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Climbable {
+    /// Whether the characters climbing are snapped to its center and only move up and down
+    /// (e.g. on a ladder), or can also move sideways (e.g. on vines)
+    pub snap: bool,
+}
+
+impl Climbable {
+    /// A ladder, snapping the characters climbing to its center
+    pub fn ladder() -> Self {
+        Climbable { snap: true }
+    }
+
+    /// Vines, which can also be climbed sideways
+    pub fn vines() -> Self {
+        Climbable { snap: false }
+    }
+}
+
+impl Component for Climbable {
+    type Storage = HashMapStorage<Self>;
+}
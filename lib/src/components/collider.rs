@@ -1,8 +1,13 @@
+use collision::layers::CollisionLayer;
 use ncollide::world::CollisionObjectHandle;
 use specs::{Component, VecStorage};
 
 pub struct Collider {
     pub collision_object_handle: CollisionObjectHandle,
+    // The layer deciding what the entity interacts with
+    pub layer: CollisionLayer,
+    // One-way colliders only block the entities landing on them from above
+    pub one_way: bool,
 }
 
 impl Component for Collider {
@@ -10,9 +15,17 @@ impl Component for Collider {
 }
 
 impl Collider {
-    pub fn new(handle: CollisionObjectHandle) -> Self {
+    pub fn new(handle: CollisionObjectHandle, layer: CollisionLayer) -> Self {
         Collider {
             collision_object_handle: handle,
+            layer,
+            one_way: false,
         }
     }
+
+    // Makes the collider one-way: it can be jumped through from below or the sides
+    pub fn one_way(mut self) -> Self {
+        self.one_way = true;
+        self
+    }
 }
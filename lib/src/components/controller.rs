@@ -0,0 +1,208 @@
+use components::input::Input;
+use nalgebra::Vector2;
+use specs::{Component, HashMapStorage};
+
+/// A component driving a moving entity from its `Input`, the way a platformer character moves
+///
+/// The entity also needs `Grounded` and `Jump` components (the jump windows are configured on
+/// the latter). The controller applies the gravity matching the jump height and time to apex,
+/// which a `GravityAffected` component only scales or turns (e.g. in a gravity zone), instead
+/// of the world's. Inside a `Climbable` trigger, holding up or down grabs it, and the entity
+/// climbs it without gravity until jumping off, letting go at the bottom or reaching the top.
+/// Deep enough in `Water`, the entity swims instead of running, kept afloat by the water.
+///
+/// Speeds are in pixels per second, accelerations in pixels per second squared and durations in
+/// seconds.
+pub struct CharacterController {
+    /// The maximum horizontal speed, when running
+    pub run_speed: f32,
+    /// How fast the entity reaches its running speed
+    pub run_acceleration: f32,
+    /// How fast the entity stops, when no direction is held
+    pub run_deceleration: f32,
+    /// The height of a full jump, in pixels
+    pub jump_height: f32,
+    /// The time to reach the top of a full jump
+    pub time_to_apex: f32,
+    /// The fraction of the upward speed kept when releasing the jump button early
+    pub jump_cut: f32,
+    /// The maximum falling speed when sliding along a wall
+    pub wall_slide_speed: f32,
+    /// The speed given by a jump off a wall, away from the wall and upwards
+    pub wall_jump_speed: Vector2<f32>,
+    /// The speed of a dash
+    pub dash_speed: f32,
+    /// How long a dash lasts
+    pub dash_duration: f32,
+    /// The time between two dashes
+    pub dash_cooldown: f32,
+    /// The speed when climbing
+    pub climb_speed: f32,
+    /// The maximum speed when swimming
+    pub swim_speed: f32,
+    /// How fast the entity reaches its swimming speed
+    pub swim_acceleration: f32,
+    // The input during the last update, to detect the buttons pressed and released
+    previous: Input,
+    // The horizontal direction the entity looks at
+    facing: f32,
+    // The direction and remaining time of the current dash
+    dash: Option<(Vector2<f32>, f32)>,
+    // The time before dashing again
+    dash_cooldown_left: f32,
+    // Whether the entity can dash again (once in the air, reset when on the ground)
+    dash_ready: bool,
+    // Whether the entity is holding on to something climbable
+    climbing: bool,
+    // Whether the entity climbed out at the top, and still holds up (not to grab it again)
+    topped_out: bool,
+    // Whether the entity is deep enough in the water to swim
+    swimming: bool,
+}
+
+impl CharacterController {
+    /// Creates a new CharacterController component, with the default tuning
+    pub fn new() -> Self {
+        CharacterController {
+            run_speed: 300.,
+            run_acceleration: 3000.,
+            run_deceleration: 4000.,
+            jump_height: 120.,
+            time_to_apex: 0.4,
+            jump_cut: 0.5,
+            wall_slide_speed: 100.,
+            wall_jump_speed: Vector2::new(300., 500.),
+            dash_speed: 900.,
+            dash_duration: 0.15,
+            dash_cooldown: 0.5,
+            climb_speed: 150.,
+            swim_speed: 200.,
+            swim_acceleration: 3000.,
+            previous: Input::default(),
+            facing: 1.,
+            dash: None,
+            dash_cooldown_left: 0.,
+            dash_ready: true,
+            climbing: false,
+            topped_out: false,
+            swimming: false,
+        }
+    }
+
+    /// Sets the height of a full jump, and the time it takes to reach it
+    pub fn with_jump(mut self, jump_height: f32, time_to_apex: f32) -> Self {
+        self.jump_height = jump_height;
+        self.time_to_apex = time_to_apex;
+        self
+    }
+
+    /// The gravity pulling the entity, matching the jump height and time to apex
+    pub fn gravity(&self) -> f32 {
+        2. * self.jump_height / (self.time_to_apex * self.time_to_apex)
+    }
+
+    /// The upward speed given by a jump, matching the jump height and time to apex
+    pub fn jump_speed(&self) -> f32 {
+        2. * self.jump_height / self.time_to_apex
+    }
+
+    /// The horizontal direction the entity looks at: -1 (left) or 1 (right)
+    pub fn facing(&self) -> f32 {
+        self.facing
+    }
+
+    /// Whether the entity is dashing
+    pub fn is_dashing(&self) -> bool {
+        self.dash.is_some()
+    }
+
+    /// Whether the entity is climbing
+    pub fn is_climbing(&self) -> bool {
+        self.climbing
+    }
+
+    /// Grabs or lets go of what the entity climbs
+    pub fn set_climbing(&mut self, climbing: bool) {
+        self.climbing = climbing;
+    }
+
+    /// Whether the entity climbed out at the top of what it climbs, up still being held
+    pub fn topped_out(&self) -> bool {
+        self.topped_out
+    }
+
+    /// Records that the entity climbed out at the top, or that up was released since
+    pub fn set_topped_out(&mut self, topped_out: bool) {
+        self.topped_out = topped_out;
+    }
+
+    /// Whether the entity is swimming
+    pub fn is_swimming(&self) -> bool {
+        self.swimming
+    }
+
+    /// Starts or stops swimming
+    pub fn set_swimming(&mut self, swimming: bool) {
+        self.swimming = swimming;
+    }
+
+    /// Remembers the new input, returning the previous one
+    pub fn remember(&mut self, input: Input) -> Input {
+        let previous = self.previous;
+        self.previous = input;
+        if input.horizontal() != 0. {
+            self.facing = input.horizontal();
+        }
+
+        previous
+    }
+
+    /// Updates the current dash (starting one if asked to, and possible) for `dt` seconds
+    ///
+    /// Returns the velocity of the dash, if dashing.
+    pub fn update_dash(
+        &mut self,
+        input: Input,
+        dash_pressed: bool,
+        on_ground: bool,
+        dt: f32,
+    ) -> Option<Vector2<f32>> {
+        self.dash_cooldown_left = (self.dash_cooldown_left - dt).max(0.);
+        if on_ground {
+            self.dash_ready = true;
+        }
+
+        if dash_pressed && self.dash.is_none() && self.dash_ready && self.dash_cooldown_left == 0. {
+            // Dashes in the direction held, or forward
+            let direction = Vector2::new(input.horizontal(), input.vertical());
+            let direction = if direction.norm() > 0. {
+                direction.normalize()
+            } else {
+                Vector2::new(self.facing, 0.)
+            };
+
+            self.dash = Some((direction, self.dash_duration));
+            self.dash_cooldown_left = self.dash_cooldown;
+            self.dash_ready = on_ground;
+        }
+
+        let (direction, left) = self.dash?;
+        self.dash = if left > dt {
+            Some((direction, left - dt))
+        } else {
+            None
+        };
+
+        Some(direction * self.dash_speed)
+    }
+}
+
+impl Default for CharacterController {
+    fn default() -> Self {
+        CharacterController::new()
+    }
+}
+
+impl Component for CharacterController {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,54 @@
+use nalgebra::Vector2;
+use specs::{Component, HashMapStorage};
+
+/// A component making the entity's trigger an area effector, acting on the moving entities
+/// overlapping it
+///
+/// Forces and impulses are in mass × pixels per second squared and mass × pixels per second:
+/// the heavier entities are affected less (see `RigidBody::mass`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AreaEffector {
+    /// Pushes the entities inside with a constant force (e.g. wind)
+    Wind { force: (f32, f32) },
+    /// Carries the entities standing inside along their ground, at the given speed (in pixels
+    /// per second, towards the right of the entities, see `Gravity::side`)
+    Conveyor { speed: f32 },
+    /// Launches the entities entering it with an impulse, after stopping them if they were
+    /// moving against it
+    BouncePad { impulse: (f32, f32) },
+    /// Knocks the entities entering it back, away from its center, with an impulse of
+    /// the given strength
+    Explosion { strength: f32 },
+}
+
+impl AreaEffector {
+    /// The force applied to the entities inside, if any
+    pub fn force(&self) -> Option<Vector2<f32>> {
+        match *self {
+            AreaEffector::Wind { force } => Some(Vector2::new(force.0, force.1)),
+            _ => None,
+        }
+    }
+
+    /// The impulse given to an entity entering the effector, from the effector's center
+    /// to the entity's, if any
+    pub fn impulse(&self, offset: Vector2<f32>) -> Option<Vector2<f32>> {
+        match *self {
+            AreaEffector::BouncePad { impulse } => Some(Vector2::new(impulse.0, impulse.1)),
+            AreaEffector::Explosion { strength } => {
+                // Straight up, from the very center
+                let direction = if offset.norm() > 0. {
+                    offset.normalize()
+                } else {
+                    Vector2::new(0., -1.)
+                };
+                Some(direction * strength)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Component for AreaEffector {
+    type Storage = HashMapStorage<Self>;
+}
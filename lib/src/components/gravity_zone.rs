@@ -0,0 +1,41 @@
+use nalgebra::Vector2;
+use specs::{Component, HashMapStorage};
+
+/// A component changing the gravity of the `GravityAffected` entities overlapping the entity's
+/// trigger (e.g. a low-gravity room, or a section where the gravity is flipped)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravityZone {
+    /// Multiplies the gravity of the entities inside
+    pub scale: f32,
+    /// The direction of the gravity inside, if it changes
+    pub direction: Option<Vector2<f32>>,
+    /// When an entity is inside several zones, the one of highest priority applies
+    pub priority: i32,
+}
+
+impl GravityZone {
+    /// Creates a new GravityZone component, scaling the gravity inside
+    pub fn new(scale: f32) -> Self {
+        GravityZone {
+            scale,
+            direction: None,
+            priority: 0,
+        }
+    }
+
+    /// Changes the direction of the gravity inside the zone
+    pub fn with_direction(mut self, direction: Vector2<f32>) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Sets the priority of the zone, over the ones it overlaps
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl Component for GravityZone {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,43 @@
+use specs::{Component, VecStorage};
+use std::f32;
+
+/// A component recording what a moving entity is touching, updated after the collisions
+/// are resolved
+pub struct Grounded {
+    /// Whether the entity stands on something
+    pub on_ground: bool,
+    /// Whether the entity touches something on its left
+    pub on_wall_left: bool,
+    /// Whether the entity touches something on its right
+    pub on_wall_right: bool,
+    /// The time since the entity last stood on something, in seconds
+    /// (0 while on the ground, infinite if it never did)
+    pub time_since_grounded: f32,
+}
+
+impl Grounded {
+    /// Creates a new Grounded component, touching nothing and never grounded yet
+    pub fn new() -> Self {
+        Grounded {
+            on_ground: false,
+            on_wall_left: false,
+            on_wall_right: false,
+            time_since_grounded: f32::INFINITY,
+        }
+    }
+
+    /// Whether the entity touches a wall, on either side
+    pub fn on_wall(&self) -> bool {
+        self.on_wall_left || self.on_wall_right
+    }
+}
+
+impl Default for Grounded {
+    fn default() -> Self {
+        Grounded::new()
+    }
+}
+
+impl Component for Grounded {
+    type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,44 @@
+use specs::{Component, VecStorage};
+
+/// A component holding the buttons held by whoever controls the entity (a player or an AI)
+///
+/// Only the current state is stored: the systems reading it keep the previous one if they
+/// need to know when a button is pressed or released.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Input {
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub jump: bool,
+    pub dash: bool,
+}
+
+impl Input {
+    /// Creates a new Input component, with no button held
+    pub fn new() -> Self {
+        Input::default()
+    }
+
+    /// The horizontal direction held: -1 (left), 0 (none or both) or 1 (right)
+    pub fn horizontal(self) -> f32 {
+        match (self.left, self.right) {
+            (true, false) => -1.,
+            (false, true) => 1.,
+            _ => 0.,
+        }
+    }
+
+    /// The vertical direction held: -1 (up), 0 (none or both) or 1 (down)
+    pub fn vertical(self) -> f32 {
+        match (self.up, self.down) {
+            (true, false) => -1.,
+            (false, true) => 1.,
+            _ => 0.,
+        }
+    }
+}
+
+impl Component for Input {
+    type Storage = VecStorage<Self>;
+}
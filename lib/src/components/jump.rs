@@ -0,0 +1,87 @@
+use specs::{Component, HashMapStorage};
+
+/// A component letting an entity jump, when it has a `Grounded` component
+///
+/// Jumping is forgiving: the entity can still jump a little while after leaving the ground
+/// (coyote time), and a press a little before landing is kept until it can jump (buffering).
+pub struct Jump {
+    /// The upward speed given by a jump
+    pub speed: f32,
+    /// How long after leaving the ground the entity can still jump, in seconds
+    pub coyote_time: f32,
+    /// How long a jump press is kept until the entity can jump, in seconds
+    pub buffer_time: f32,
+    // The time since the pending jump press, if any
+    buffered: Option<f32>,
+    // Whether the entity has not jumped since it was last on the ground
+    ready: bool,
+}
+
+impl Jump {
+    /// Creates a new Jump component, with the default windows
+    pub fn new(speed: f32) -> Self {
+        Jump {
+            speed,
+            coyote_time: 0.1,
+            buffer_time: 0.1,
+            buffered: None,
+            ready: false,
+        }
+    }
+
+    /// Sets the coyote time and the jump buffering windows, in seconds
+    pub fn with_windows(mut self, coyote_time: f32, buffer_time: f32) -> Self {
+        self.coyote_time = coyote_time;
+        self.buffer_time = buffer_time;
+        self
+    }
+
+    /// Presses the jump button: the entity jumps as soon as it can, within the buffer window
+    pub fn press(&mut self) {
+        self.buffered = Some(0.);
+    }
+
+    /// Forgets the pending jump press (e.g. when it was used for another kind of jump)
+    pub fn cancel(&mut self) {
+        self.buffered = None;
+    }
+
+    /// Whether a jump press is waiting for the entity to be able to jump
+    pub fn is_buffered(&self) -> bool {
+        self.buffered.is_some()
+    }
+
+    /// Tries to jump, given whether the entity is on the ground and the time since it
+    /// last was, then lets `elapsed` seconds pass for the pending press
+    ///
+    /// Returns whether the entity jumps.
+    pub fn update(&mut self, on_ground: bool, time_since_grounded: f32, elapsed: f32) -> bool {
+        if on_ground {
+            self.ready = true;
+        }
+
+        let pressed = match self.buffered {
+            Some(age) => age <= self.buffer_time,
+            None => false,
+        };
+        let can_jump = self.ready && (on_ground || time_since_grounded <= self.coyote_time);
+
+        if pressed && can_jump {
+            self.buffered = None;
+            self.ready = false;
+            return true;
+        }
+
+        // Forget the press once the buffer window is over
+        self.buffered = match self.buffered {
+            Some(age) if age + elapsed <= self.buffer_time => Some(age + elapsed),
+            _ => None,
+        };
+
+        false
+    }
+}
+
+impl Component for Jump {
+    type Storage = HashMapStorage<Self>;
+}
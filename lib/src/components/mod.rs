@@ -1,4 +1,15 @@
+pub mod climbable;
 pub mod collider;
+pub mod controller;
+pub mod effector;
+pub mod gravity_zone;
+pub mod grounded;
+pub mod input;
+pub mod jump;
 pub mod moving;
+pub mod platform;
 pub mod rect_drawable;
+pub mod rigid_body;
 pub mod transform;
+pub mod trigger;
+pub mod water;
@@ -1,10 +1,34 @@
-use nalgebra::Translation2;
+use components::gravity_zone::GravityZone;
+use nalgebra::{Translation2, Vector2};
 use specs::{Component, VecStorage};
 
+/// The gravity pulling the entities by default, in pixels per second squared
+pub const DEFAULT_GRAVITY: f32 = 1500.;
+
 /// A component that allows an entity to move
 pub struct Moving {
-    /// The current velocity of the moving object
+    /// The current velocity of the moving object, in pixels per second
     pub velocity: Translation2<f32>,
+    /// The acceleration to apply during the next update, in pixels per second squared
+    /// (reset once applied)
+    pub acceleration: Vector2<f32>,
+    /// The sum of the forces to apply during the next update, in mass × pixels per second
+    /// squared (reset once applied)
+    pub force: Vector2<f32>,
+    /// The sum of the impulses to apply during the next update, in mass × pixels per second
+    /// (reset once applied)
+    pub impulse: Vector2<f32>,
+    /// The maximum falling speed, in pixels per second
+    pub terminal_velocity: Option<f32>,
+    /// How fast the velocity decays in the air, as a fraction lost per second
+    pub drag: f32,
+    /// How fast the velocity along the ground decreases on it, in pixels per second squared
+    /// (derived from the `RigidBody` of the bodies, when they move)
+    pub friction: f32,
+    /// The position before the last move, if it moved already
+    pub previous_position: Option<Vector2<f32>>,
+    /// Lets the entity fall through the one-way platforms (e.g. when holding down)
+    pub drop_through: bool,
 }
 
 impl Moving {
@@ -12,14 +36,169 @@ impl Moving {
     pub fn new() -> Self {
         Moving {
             velocity: Translation2::new(0., 0.),
+            acceleration: Vector2::new(0., 0.),
+            force: Vector2::new(0., 0.),
+            impulse: Vector2::new(0., 0.),
+            terminal_velocity: None,
+            drag: 0.,
+            friction: 0.,
+            previous_position: None,
+            drop_through: false,
         }
     }
 
+    /// Limits the falling speed, in pixels per second
+    pub fn with_terminal_velocity(mut self, terminal_velocity: f32) -> Self {
+        self.terminal_velocity = Some(terminal_velocity);
+        self
+    }
+
+    /// Sets the air drag, as a fraction of the velocity lost per second
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// Sets the ground friction, in pixels per second squared
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
     /// Applies a force to the moving entity
+    ///
+    /// The velocity changes right away, regardless of the mass: see `add_force`
+    /// and `add_impulse` instead.
     pub fn apply_force(&mut self, force: Translation2<f32>) {
         self.velocity.vector.x += force.vector.x;
         self.velocity.vector.y += force.vector.y;
     }
+
+    /// Adds a force, accelerating the entity during the next update
+    pub fn add_force(&mut self, force: Vector2<f32>) {
+        self.force += force;
+    }
+
+    /// Adds an impulse, changing the velocity of the entity at the next update
+    pub fn add_impulse(&mut self, impulse: Vector2<f32>) {
+        self.impulse += impulse;
+    }
+
+    /// Turns the forces into acceleration and the impulses into velocity, for an entity
+    /// of the given inverse mass (see `RigidBody::inverse_mass`)
+    pub fn apply_forces(&mut self, inverse_mass: f32) {
+        self.acceleration += self.force * inverse_mass;
+        self.velocity.vector += self.impulse * inverse_mass;
+
+        self.force = Vector2::new(0., 0.);
+        self.impulse = Vector2::new(0., 0.);
+    }
+
+    /// Moves the entity for `dt` seconds (when nothing blocks it): integrates the
+    /// acceleration up to the terminal velocity, slowed down by the drag (in the air)
+    /// or the friction (on the ground)
+    ///
+    /// `down` is the direction its gravity pulls it towards (normalized): it falls along it,
+    /// and rubs against the ground across it.
+    ///
+    /// Returns the motion, in pixels. The position is integrated exactly for a constant
+    /// acceleration, drag and friction, so the motion doesn't depend on the frame rate.
+    pub fn integrate(&mut self, dt: f32, on_ground: bool, down: Vector2<f32>) -> Vector2<f32> {
+        let acceleration = self.acceleration;
+        self.acceleration = Vector2::new(0., 0.);
+
+        // Along the ground, and towards it
+        let side = Vector2::new(down.y, -down.x);
+        let start = self.velocity.vector;
+        let (start_side, start_down) = (start.dot(&side), start.dot(&down));
+        let (acceleration_side, acceleration_down) =
+            (acceleration.dot(&side), acceleration.dot(&down));
+
+        let drag = if on_ground { 0. } else { self.drag };
+        let (motion_side, velocity_side) = if on_ground {
+            advance_with_friction(start_side, acceleration_side, self.friction, dt)
+        } else {
+            advance(start_side, acceleration_side, drag, dt)
+        };
+        let (mut motion_down, mut velocity_down) = advance(start_down, acceleration_down, drag, dt);
+
+        if let Some(terminal_velocity) = self.terminal_velocity {
+            if velocity_down > terminal_velocity {
+                motion_down = if start_down < terminal_velocity {
+                    // Accelerates up to the terminal velocity, then keeps falling at it
+                    let until =
+                        time_to_reach(start_down, acceleration_down, drag, terminal_velocity);
+                    advance(start_down, acceleration_down, drag, until).0
+                        + terminal_velocity * (dt - until)
+                } else {
+                    terminal_velocity * dt
+                };
+                velocity_down = terminal_velocity;
+            }
+        }
+
+        self.velocity.vector = side * velocity_side + down * velocity_down;
+        side * motion_side + down * motion_down
+    }
+}
+
+// The motion and the speed along an axis after `dt` seconds, starting at `speed`, with a
+// constant `acceleration` and `drag` (the fraction of the speed lost per second)
+fn advance(speed: f32, acceleration: f32, drag: f32, dt: f32) -> (f32, f32) {
+    if drag == 0. {
+        return (
+            speed * dt + acceleration * dt * dt / 2.,
+            speed + acceleration * dt,
+        );
+    }
+
+    // Exponential decay towards the speed at which the drag cancels the acceleration,
+    // which doesn't depend on how the time is divided
+    let limit = acceleration / drag;
+    let decay = (-drag * dt).exp();
+    (
+        limit * dt + (speed - limit) * (1. - decay) / drag,
+        limit + (speed - limit) * decay,
+    )
+}
+
+// The time it takes to go from `speed` to `target` along an axis (see `advance`), the target
+// being reached
+fn time_to_reach(speed: f32, acceleration: f32, drag: f32, target: f32) -> f32 {
+    if drag == 0. {
+        return (target - speed) / acceleration;
+    }
+
+    let limit = acceleration / drag;
+    ((speed - limit) / (target - limit)).ln() / drag
+}
+
+// Like `advance`, but slowed down at a constant rate by the `friction`, which opposes the
+// motion (or the acceleration, when not moving) without ever making the entity go backwards
+fn advance_with_friction(speed: f32, acceleration: f32, friction: f32, dt: f32) -> (f32, f32) {
+    // Too weak to overcome the friction
+    if speed == 0. && acceleration.abs() <= friction {
+        return (0., 0.);
+    }
+
+    let direction = if speed != 0. {
+        speed.signum()
+    } else {
+        acceleration.signum()
+    };
+    let net = acceleration - friction * direction;
+
+    // Stops before the end, then only moves again if the acceleration overcomes the friction
+    if speed * net < 0. {
+        let until = -speed / net;
+        if until < dt {
+            let (stopping, _) = advance(speed, net, 0., until);
+            let (motion, speed) = advance_with_friction(0., acceleration, friction, dt - until);
+            return (stopping + motion, speed);
+        }
+    }
+
+    advance(speed, net, 0., dt)
 }
 
 impl Default for Moving {
@@ -33,13 +212,65 @@ impl Component for Moving {
 }
 
 /// A component that makes an entity affected by the gravity
-#[derive(Default)]
-pub struct GravityAffected {}
+pub struct GravityAffected {
+    /// How strong the gravity is for the entity, relatively to the world's (0 makes it float)
+    pub scale: f32,
+    /// The direction of the gravity for the entity, instead of the world's
+    pub direction: Option<Vector2<f32>>,
+    // The gravity zone the entity is in, if any
+    zone: Option<GravityZone>,
+}
 
 impl GravityAffected {
-    /// Creates a new GravityAffected marker
+    /// Creates a new GravityAffected component, subject to the world's gravity
     pub fn new() -> Self {
-        GravityAffected {}
+        GravityAffected {
+            scale: 1.,
+            direction: None,
+            zone: None,
+        }
+    }
+
+    /// Scales the gravity for the entity
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Changes the direction of the gravity for the entity
+    pub fn with_direction(mut self, direction: Vector2<f32>) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// The gravity zone the entity is in, if any
+    pub fn zone(&self) -> Option<&GravityZone> {
+        self.zone.as_ref()
+    }
+
+    /// Puts the entity in a gravity zone, or out of any with `None`
+    pub fn set_zone(&mut self, zone: Option<GravityZone>) {
+        self.zone = zone;
+    }
+
+    /// The gravity for the entity, given the world's: its zone's direction (or else its own)
+    /// replaces the world's, and both its scale and its zone's apply
+    pub fn gravity(&self, world_gravity: Vector2<f32>) -> Vector2<f32> {
+        let direction = self.zone.and_then(|zone| zone.direction).or(self.direction);
+        let gravity = match direction {
+            Some(direction) if direction.norm() > 0. => {
+                direction.normalize() * world_gravity.norm()
+            }
+            _ => world_gravity,
+        };
+
+        gravity * self.scale * self.zone.map_or(1., |zone| zone.scale)
+    }
+}
+
+impl Default for GravityAffected {
+    fn default() -> Self {
+        GravityAffected::new()
     }
 }
 
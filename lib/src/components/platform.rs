@@ -0,0 +1,225 @@
+use nalgebra::Vector2;
+use specs::{Component, HashMapStorage};
+
+// How many waypoints a platform can go past in a single update
+const MAX_WAYPOINTS_PER_UPDATE: usize = 64;
+
+/// How a platform goes on once at the last waypoint of its path
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathMode {
+    /// Goes back to the first waypoint, then follows the path again
+    Loop,
+    /// Follows the path backwards, then forwards again
+    PingPong,
+}
+
+impl Default for PathMode {
+    fn default() -> Self {
+        PathMode::Loop
+    }
+}
+
+/// How a platform moves between two waypoints
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    /// At a constant speed
+    Linear,
+    /// Speeding up when leaving a waypoint, and slowing down when reaching the next one
+    EaseInOut,
+}
+
+impl Easing {
+    /// The fraction of the way done, given the fraction of the time spent (both in [0, 1])
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+        }
+    }
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+/// Where a platform is along its path, as replicated over the network
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PlatformState {
+    /// The index of the platform in the map
+    pub index: usize,
+    /// The segment of the path the platform is on
+    pub segment: usize,
+    /// The fraction of the segment's duration spent
+    pub progress: f32,
+    /// Whether the platform follows its path forwards (always the case when looping)
+    pub forward: bool,
+}
+
+/// A component making an entity a kinematic platform, following a path of waypoints
+///
+/// Platforms are not `Moving`: the moving entities collide with them like with any
+/// static collider, and the ones standing on them are carried along.
+pub struct MovingPlatform {
+    /// The index of the platform in the map, identifying it over the network
+    pub index: usize,
+    /// The positions (of the center) the platform goes through
+    pub waypoints: Vec<Vector2<f32>>,
+    /// The speed of the platform, in pixels per second (before easing)
+    pub speed: f32,
+    pub mode: PathMode,
+    pub easing: Easing,
+    /// The velocity of the platform during the last update, in pixels per second
+    pub velocity: Vector2<f32>,
+    // The segment of the path the platform is on, starting from the waypoint of same index
+    // (or ending there, when going backwards)
+    segment: usize,
+    // The fraction of the segment's duration spent
+    progress: f32,
+    forward: bool,
+}
+
+impl MovingPlatform {
+    /// Creates a new MovingPlatform component, at the first waypoint
+    pub fn new(index: usize, waypoints: Vec<Vector2<f32>>, speed: f32) -> Self {
+        MovingPlatform {
+            index,
+            waypoints,
+            speed,
+            mode: PathMode::default(),
+            easing: Easing::default(),
+            velocity: Vector2::new(0., 0.),
+            segment: 0,
+            progress: 0.,
+            forward: true,
+        }
+    }
+
+    /// Sets how the platform goes on at the end of its path
+    pub fn with_mode(mut self, mode: PathMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets how the platform moves between two waypoints
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Where the platform is along its path
+    pub fn state(&self) -> PlatformState {
+        PlatformState {
+            index: self.index,
+            segment: self.segment,
+            progress: self.progress,
+            forward: self.forward,
+        }
+    }
+
+    /// Puts the platform where the state tells (e.g. as received from the server)
+    pub fn set_state(&mut self, state: &PlatformState) {
+        if state.segment < self.segments() {
+            self.segment = state.segment;
+            self.progress = state.progress.max(0.).min(1.);
+            self.forward = state.forward || self.mode == PathMode::Loop;
+        }
+    }
+
+    /// The current position (of the center) of the platform
+    pub fn position(&self) -> Vector2<f32> {
+        match self.endpoints() {
+            Some((from, to)) => from + (to - from) * self.easing.apply(self.progress),
+            None => self
+                .waypoints
+                .first()
+                .cloned()
+                .unwrap_or_else(|| Vector2::new(0., 0.)),
+        }
+    }
+
+    /// Moves the platform along its path for `dt` seconds, returning its new position
+    pub fn advance(&mut self, dt: f32) -> Vector2<f32> {
+        let start = self.position();
+        let mut time = dt;
+
+        for _ in 0..MAX_WAYPOINTS_PER_UPDATE {
+            let (from, to) = match self.endpoints() {
+                Some(endpoints) => endpoints,
+                None => break,
+            };
+            if self.speed <= 0. {
+                break;
+            }
+
+            // The time left to reach the end of the segment
+            let duration = (to - from).norm() / self.speed;
+            let left = (1. - self.progress) * duration;
+
+            if time < left {
+                self.progress += time / duration;
+                break;
+            }
+
+            time -= left;
+            self.next_segment();
+        }
+
+        let position = self.position();
+        if dt > 0. {
+            self.velocity = (position - start) / dt;
+        }
+
+        position
+    }
+
+    // The number of segments of the path
+    fn segments(&self) -> usize {
+        match (self.waypoints.len(), self.mode) {
+            (0, _) | (1, _) => 0,
+            (waypoints, PathMode::Loop) => waypoints,
+            (waypoints, PathMode::PingPong) => waypoints - 1,
+        }
+    }
+
+    // The start and the end of the current segment, if there's one
+    fn endpoints(&self) -> Option<(Vector2<f32>, Vector2<f32>)> {
+        if self.segments() == 0 {
+            return None;
+        }
+
+        let from = self.waypoints[self.segment];
+        let to = self.waypoints[(self.segment + 1) % self.waypoints.len()];
+
+        if self.forward {
+            Some((from, to))
+        } else {
+            Some((to, from))
+        }
+    }
+
+    // Goes to the start of the next segment
+    fn next_segment(&mut self) {
+        let last = self.segments() - 1;
+        self.progress = 0.;
+
+        match self.mode {
+            PathMode::Loop => self.segment = (self.segment + 1) % (last + 1),
+            PathMode::PingPong => {
+                if self.forward && self.segment < last {
+                    self.segment += 1;
+                } else if !self.forward && self.segment > 0 {
+                    self.segment -= 1;
+                } else {
+                    // Turn back on the same segment
+                    self.forward = !self.forward;
+                }
+            }
+        }
+    }
+}
+
+impl Component for MovingPlatform {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,60 @@
+use specs::{Component, HashMapStorage};
+
+/// A component making a moving entity a dynamic body (e.g. a crate or a boulder): it is pushed
+/// by the moving entities touching it, pushes them back, and can be stacked and stood on
+///
+/// The moving entities without a body (e.g. the players) touch the bodies as if they had
+/// the default one.
+pub struct RigidBody {
+    /// How hard the body is to push, relatively to what pushes it (infinite for immovable bodies)
+    pub mass: f32,
+    /// How much the body bounces back after an impact, between 0 (it stops) and 1 (it bounces
+    /// back at the same speed)
+    pub restitution: f32,
+    /// How much the body resists sliding against what it touches (a Coulomb friction coefficient)
+    ///
+    /// On the ground, its gravity presses it down: it slows down by this times its gravity.
+    pub friction: f32,
+}
+
+impl RigidBody {
+    /// Creates a new RigidBody component of the given mass, which doesn't bounce
+    pub fn new(mass: f32) -> Self {
+        RigidBody {
+            mass,
+            restitution: 0.,
+            friction: 0.5,
+        }
+    }
+
+    /// Sets how much the body bounces back after an impact
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    /// Sets how much the body resists sliding
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// The inverse of the mass, 0 for the bodies that can't be pushed
+    pub fn inverse_mass(&self) -> f32 {
+        if self.mass > 0. && self.mass.is_finite() {
+            1. / self.mass
+        } else {
+            0.
+        }
+    }
+}
+
+impl Default for RigidBody {
+    fn default() -> Self {
+        RigidBody::new(1.)
+    }
+}
+
+impl Component for RigidBody {
+    type Storage = HashMapStorage<Self>;
+}
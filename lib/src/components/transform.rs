@@ -1,3 +1,4 @@
+use collision::oriented::OrientedRect;
 use nalgebra::{Isometry2, Point2, Vector2};
 use specs::{Component, VecStorage};
 use types::Rect;
@@ -27,6 +28,7 @@ impl Transform {
         }
     }
 
+    /// The rectangle covered by the entity, as if it wasn't turned (see `aabb` otherwise)
     pub fn as_rect(&self) -> Rect {
         Rect::new(
             self.isometry.translation.vector.x - self.size.x / 2.,
@@ -35,6 +37,26 @@ impl Transform {
             self.size.y,
         )
     }
+
+    /// The rectangle covered by the entity, turned by its rotation
+    pub fn oriented_rect(&self) -> OrientedRect {
+        OrientedRect::new(
+            self.isometry.translation.vector,
+            Vector2::new(self.size.x / 2., self.size.y / 2.),
+            self.isometry.rotation.angle(),
+        )
+    }
+
+    /// The corners of the entity, once turned by its rotation
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        self.oriented_rect().corners()
+    }
+
+    /// The axis-aligned bounds of the entity, once turned by its rotation (e.g. for the
+    /// broadphase, or culling what's off screen)
+    pub fn aabb(&self) -> Rect {
+        self.oriented_rect().bounds()
+    }
 }
 
 impl Component for Transform {
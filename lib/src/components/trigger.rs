@@ -0,0 +1,65 @@
+use specs::{Component, Entity, HashMapStorage};
+use std::collections::HashSet;
+
+/// What happens when an entity enters a trigger
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TriggerKind {
+    /// Saves the progression of the entity
+    Checkpoint,
+    /// Kills the entity
+    KillZone,
+    /// Ends the level
+    LevelExit,
+    /// Runs the named script
+    Script(String),
+    /// Changes the gravity of the entities inside (see `GravityZone`)
+    Gravity,
+    /// Pushes or launches the entities inside (see `AreaEffector`)
+    Effector,
+    /// Can be climbed by the characters inside (see `Climbable`)
+    Climbable,
+    /// Makes the entities inside float and swim (see `Water`)
+    Water,
+}
+
+/// A component turning the entity's collider into a sensor: it doesn't block anything,
+/// but reports the entities overlapping it as trigger events
+pub struct Trigger {
+    pub kind: TriggerKind,
+    // The entities currently overlapping the trigger
+    overlapping: HashSet<Entity>,
+}
+
+impl Trigger {
+    /// Creates a new Trigger component, overlapped by nothing yet
+    pub fn new(kind: TriggerKind) -> Self {
+        Trigger {
+            kind,
+            overlapping: HashSet::new(),
+        }
+    }
+
+    /// Whether the entity is currently overlapping the trigger
+    pub fn is_overlapped_by(&self, entity: Entity) -> bool {
+        self.overlapping.contains(&entity)
+    }
+
+    /// The entities currently overlapping the trigger
+    pub fn overlapping(&self) -> &HashSet<Entity> {
+        &self.overlapping
+    }
+
+    /// Starts tracking an entity overlapping the trigger, returns `false` if it already was
+    pub fn enter(&mut self, entity: Entity) -> bool {
+        self.overlapping.insert(entity)
+    }
+
+    /// Stops tracking an entity overlapping the trigger, returns `false` if it wasn't
+    pub fn exit(&mut self, entity: Entity) -> bool {
+        self.overlapping.remove(&entity)
+    }
+}
+
+impl Component for Trigger {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,44 @@
+use specs::{Component, HashMapStorage};
+use types::Rect;
+
+/// A component making the entity's trigger a volume of water, pushing up the entities affected
+/// by the gravity inside and slowing them down
+///
+/// The buoyancy is the density times the fraction of the entity under the surface, times its
+/// gravity: the entities lighter than that float (see `RigidBody::mass`). The drag is a force
+/// against the velocity, in mass per second, also in proportion to the fraction submerged.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Water {
+    pub density: f32,
+    pub drag: f32,
+}
+
+impl Water {
+    /// Creates a new Water component
+    pub fn new(density: f32, drag: f32) -> Self {
+        Water { density, drag }
+    }
+}
+
+impl Default for Water {
+    // Floats the entities of mass 1 half submerged
+    fn default() -> Self {
+        Water::new(2., 3.)
+    }
+}
+
+/// The fraction of `rect` under the surface of the `volume` of water it overlaps, from 0 (above
+/// it) to 1 (fully submerged)
+pub fn submerged(rect: &Rect, volume: &Rect) -> f32 {
+    if rect.w <= 0. {
+        return 0.;
+    }
+
+    let top = rect.y.max(volume.y);
+    let bottom = (rect.y + rect.w).min(volume.y + volume.w);
+    ((bottom - top) / rect.w).max(0.).min(1.)
+}
+
+impl Component for Water {
+    type Storage = HashMapStorage<Self>;
+}
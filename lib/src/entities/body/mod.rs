@@ -0,0 +1,83 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use components::collider::Collider;
+use components::grounded::Grounded;
+use components::moving::{GravityAffected, Moving};
+use components::rect_drawable::RectDrawable;
+use components::rigid_body::RigidBody;
+use components::transform::Transform;
+use entities::game_entity::GameEntity;
+use nalgebra::{Point2, Vector2};
+use specs::{Entity, World};
+use types::Color;
+
+// The maximum falling speed, in pixels per second
+const TERMINAL_VELOCITY: f32 = 900.;
+
+pub struct BodyEntity(Entity);
+
+impl BodyEntity {
+    // The underlying SPECS entity
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+// A dynamic body, like a crate or a boulder, that can be pushed, stacked and stood on
+pub struct Body {
+    position: Point2<f32>,
+    size: Point2<f32>,
+    color: Color,
+    body: RigidBody,
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body {
+            position: Point2::new(0.0, 0.0),
+            size: Point2::new(32.0, 32.0),
+            color: Color::new(0.6, 0.4, 0.2, 1.0),
+            body: RigidBody::default(),
+        }
+    }
+}
+
+impl Body {
+    pub fn new(position: Point2<f32>, size: Point2<f32>, body: RigidBody) -> Self {
+        Body {
+            position,
+            size,
+            body,
+            ..Body::default()
+        }
+    }
+}
+
+impl GameEntity for Body {
+    type Entity = BodyEntity;
+
+    fn add_to_world(self, world: &mut World) -> Self::Entity {
+        let transform = Transform::new(Vector2::new(self.position.x, self.position.y), self.size);
+        let moving = Moving::new().with_terminal_velocity(TERMINAL_VELOCITY);
+
+        let entity: Entity = world
+            .create_entity()
+            .with(RectDrawable::new(self.color))
+            .with(moving)
+            .with(GravityAffected::new())
+            .with(Grounded::new())
+            .with(self.body)
+            .build();
+
+        // Register the entity's shape in the collision world
+        let collider = world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            &transform,
+            CollisionLayer::Body,
+        );
+        world.write::<Transform>().insert(entity, transform);
+        world.write::<Collider>().insert(entity, collider);
+
+        BodyEntity(entity)
+    }
+}
@@ -1,3 +1,6 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use components::collider::Collider;
 use components::rect_drawable::RectDrawable;
 use components::transform::Transform;
 use entities::game_entity::GameEntity;
@@ -7,10 +10,18 @@ use types::Color;
 
 pub struct GroundEntity(Entity);
 
+impl GroundEntity {
+    // The underlying SPECS entity
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
 pub struct Ground {
     position: Point2<f32>,
     size: Point2<f32>,
     color: Color,
+    one_way: bool,
 }
 
 impl Default for Ground {
@@ -19,6 +30,7 @@ impl Default for Ground {
             position: Point2::new(0.0, 0.0),
             size: Point2::new(32.0, 32.0),
             color: Color::new(1.0, 0.0, 0.0, 1.0),
+            one_way: false,
         }
     }
 }
@@ -29,23 +41,40 @@ impl Ground {
             position,
             size,
             color,
+            one_way: false,
         }
     }
+
+    // Turns the ground into a one-way platform, that can be jumped through from below
+    pub fn one_way(mut self) -> Self {
+        self.one_way = true;
+        self
+    }
 }
 
 impl GameEntity for Ground {
     type Entity = GroundEntity;
 
     fn add_to_world(self, world: &mut World) -> Self::Entity {
+        let transform = Transform::new(Vector2::new(self.position.x, self.position.y), self.size);
+
         let entity: Entity = world
             .create_entity()
-            .with(Transform::new(
-                Vector2::new(self.position.x, self.position.y),
-                self.size,
-            ))
             .with(RectDrawable::new(self.color))
             .build();
 
+        // Register the entity's shape in the collision world
+        let mut collider = world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            &transform,
+            CollisionLayer::Terrain,
+        );
+        if self.one_way {
+            collider = collider.one_way();
+        }
+        world.write::<Transform>().insert(entity, transform);
+        world.write::<Collider>().insert(entity, collider);
+
         GroundEntity(entity)
     }
 }
@@ -1,3 +1,6 @@
+pub mod body;
 pub mod game_entity;
 pub mod ground;
+pub mod platform;
 pub mod player;
+pub mod trigger;
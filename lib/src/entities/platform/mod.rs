@@ -0,0 +1,76 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use components::collider::Collider;
+use components::platform::MovingPlatform;
+use components::rect_drawable::RectDrawable;
+use components::transform::Transform;
+use entities::game_entity::GameEntity;
+use map::PlatformData;
+use nalgebra::{Point2, Vector2};
+use specs::{Entity, World};
+use types::Color;
+
+pub struct PlatformEntity(Entity);
+
+impl PlatformEntity {
+    // The underlying SPECS entity
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+pub struct Platform {
+    index: usize,
+    data: PlatformData,
+    color: Color,
+}
+
+impl Platform {
+    // A moving platform, the `index`-th of its map
+    pub fn new(index: usize, data: PlatformData) -> Self {
+        Platform {
+            index,
+            data,
+            color: Color::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl GameEntity for Platform {
+    type Entity = PlatformEntity;
+
+    fn add_to_world(self, world: &mut World) -> Self::Entity {
+        let waypoints = self
+            .data
+            .waypoints
+            .iter()
+            .map(|&(x, y)| Vector2::new(x, y))
+            .collect();
+        let platform = MovingPlatform::new(self.index, waypoints, self.data.speed)
+            .with_mode(self.data.mode)
+            .with_easing(self.data.easing);
+
+        let size = Point2::new(self.data.size.0, self.data.size.1);
+        let transform = Transform::new(platform.position(), size);
+
+        let entity: Entity = world
+            .create_entity()
+            .with(RectDrawable::new(self.color))
+            .with(platform)
+            .build();
+
+        // Register the entity's shape in the collision world, as part of the terrain
+        let mut collider = world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            &transform,
+            CollisionLayer::Terrain,
+        );
+        if self.data.one_way {
+            collider = collider.one_way();
+        }
+        world.write::<Transform>().insert(entity, transform);
+        world.write::<Collider>().insert(entity, collider);
+
+        PlatformEntity(entity)
+    }
+}
@@ -1,3 +1,10 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use components::collider::Collider;
+use components::controller::CharacterController;
+use components::grounded::Grounded;
+use components::input::Input;
+use components::jump::Jump;
 use components::moving::{GravityAffected, Moving};
 use components::rect_drawable::RectDrawable;
 use components::transform::Transform;
@@ -6,8 +13,18 @@ use nalgebra::{Point2, Vector2};
 use specs::{Entity, World};
 use types::Color;
 
+// The maximum falling speed, in pixels per second
+const TERMINAL_VELOCITY: f32 = 900.;
+
 pub struct PlayerEntity(Entity);
 
+impl PlayerEntity {
+    // The underlying SPECS entity
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
 pub struct Player {
     position: Point2<f32>,
     size: Point2<f32>,
@@ -38,17 +55,31 @@ impl GameEntity for Player {
     type Entity = PlayerEntity;
 
     fn add_to_world(self, world: &mut World) -> Self::Entity {
+        let transform = Transform::new(Vector2::new(self.position.x, self.position.y), self.size);
+
+        let controller = CharacterController::new();
+        let jump = Jump::new(controller.jump_speed());
+
         let entity: Entity = world
             .create_entity()
-            .with(Transform::new(
-                Vector2::new(self.position.x, self.position.y),
-                self.size,
-            ))
             .with(RectDrawable::new(self.color))
-            .with(Moving::new())
+            .with(Moving::new().with_terminal_velocity(TERMINAL_VELOCITY))
             .with(GravityAffected::new())
+            .with(Grounded::new())
+            .with(jump)
+            .with(Input::new())
+            .with(controller)
             .build();
 
+        // Register the entity's shape in the collision world
+        let collider = world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            &transform,
+            CollisionLayer::Player,
+        );
+        world.write::<Transform>().insert(entity, transform);
+        world.write::<Collider>().insert(entity, collider);
+
         PlayerEntity(entity)
     }
 }
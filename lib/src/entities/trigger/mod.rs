@@ -0,0 +1,111 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use components::climbable::Climbable;
+use components::collider::Collider;
+use components::effector::AreaEffector;
+use components::gravity_zone::GravityZone;
+use components::transform::Transform;
+use components::trigger::{Trigger, TriggerKind};
+use components::water::Water;
+use entities::game_entity::GameEntity;
+use nalgebra::{Point2, Vector2};
+use specs::{Entity, World};
+
+pub struct TriggerZoneEntity(Entity);
+
+impl TriggerZoneEntity {
+    // The underlying SPECS entity
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+// An invisible zone, reporting the entities going through it
+pub struct TriggerZone {
+    position: Point2<f32>,
+    size: Point2<f32>,
+    kind: TriggerKind,
+    gravity: Option<GravityZone>,
+    effector: Option<AreaEffector>,
+    climbable: Option<Climbable>,
+    water: Option<Water>,
+}
+
+impl TriggerZone {
+    pub fn new(position: Point2<f32>, size: Point2<f32>, kind: TriggerKind) -> Self {
+        TriggerZone {
+            position,
+            size,
+            kind,
+            gravity: None,
+            effector: None,
+            climbable: None,
+            water: None,
+        }
+    }
+
+    // A zone changing the gravity of the entities inside
+    pub fn gravity(position: Point2<f32>, size: Point2<f32>, zone: GravityZone) -> Self {
+        TriggerZone {
+            gravity: Some(zone),
+            ..TriggerZone::new(position, size, TriggerKind::Gravity)
+        }
+    }
+
+    // An area effector, acting on the moving entities inside
+    pub fn effector(position: Point2<f32>, size: Point2<f32>, effector: AreaEffector) -> Self {
+        TriggerZone {
+            effector: Some(effector),
+            ..TriggerZone::new(position, size, TriggerKind::Effector)
+        }
+    }
+
+    // A climbable zone, like a ladder
+    pub fn climbable(position: Point2<f32>, size: Point2<f32>, climbable: Climbable) -> Self {
+        TriggerZone {
+            climbable: Some(climbable),
+            ..TriggerZone::new(position, size, TriggerKind::Climbable)
+        }
+    }
+
+    // A volume of water
+    pub fn water(position: Point2<f32>, size: Point2<f32>, water: Water) -> Self {
+        TriggerZone {
+            water: Some(water),
+            ..TriggerZone::new(position, size, TriggerKind::Water)
+        }
+    }
+}
+
+impl GameEntity for TriggerZone {
+    type Entity = TriggerZoneEntity;
+
+    fn add_to_world(self, world: &mut World) -> Self::Entity {
+        let transform = Transform::new(Vector2::new(self.position.x, self.position.y), self.size);
+
+        let entity: Entity = world.create_entity().with(Trigger::new(self.kind)).build();
+        if let Some(zone) = self.gravity {
+            world.write::<GravityZone>().insert(entity, zone);
+        }
+        if let Some(effector) = self.effector {
+            world.write::<AreaEffector>().insert(entity, effector);
+        }
+        if let Some(climbable) = self.climbable {
+            world.write::<Climbable>().insert(entity, climbable);
+        }
+        if let Some(water) = self.water {
+            world.write::<Water>().insert(entity, water);
+        }
+
+        // Triggers are not solid: the collider only reports proximities
+        let collider = world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            &transform,
+            CollisionLayer::Trigger,
+        );
+        world.write::<Transform>().insert(entity, transform);
+        world.write::<Collider>().insert(entity, collider);
+
+        TriggerZoneEntity(entity)
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use serde_json;
+
+use components::effector::AreaEffector;
+use components::platform::{Easing, PathMode};
+use components::trigger::TriggerKind;
+use components::water::Water;
+use types::Rect;
+
+/// Size of a tile, in pixels
+pub const TILE_SIZE: f32 = 40.;
+
+/// The tile at the top left corner of the grid (the cell (0, 0))
+///
+/// Tiles are indexed bottom-up, while the grid cells are indexed top-down, like the screen.
+const GRID_ORIGIN: (i32, i32) = (-10, 14);
+
+const DEBUG_MAP: &[(i32, i32)] = &[
+    (-6, 4), // stair
+    (-6, 3),
+    (-5, 3),
+    (-5, 2),
+    (-4, 2),
+    (-4, 1),
+    (-3, 1),
+    (-3, 0),
+    (-2, 0), // ground
+    (-1, 0),
+    (0, 0),
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (4, 0),
+    (5, 0),
+    (5, 1), // wall
+    (5, 2),
+    (5, 3),
+    (5, 4),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    /// One-way blocks can be jumped through from below
+    pub one_way: bool,
+}
+
+/// A trigger volume (e.g. a checkpoint, or a scripted event)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TriggerData {
+    /// The position of the center of the volume, in pixels
+    pub position: (f32, f32),
+    /// The size of the volume, in pixels
+    pub size: (f32, f32),
+    pub kind: TriggerKind,
+}
+
+/// A moving platform, following a path of waypoints
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlatformData {
+    /// The size of the platform, in pixels
+    pub size: (f32, f32),
+    /// The positions (of the center) the platform goes through, in pixels
+    pub waypoints: Vec<(f32, f32)>,
+    /// The speed of the platform, in pixels per second
+    pub speed: f32,
+    #[serde(default)]
+    pub mode: PathMode,
+    #[serde(default)]
+    pub easing: Easing,
+    /// One-way platforms can be jumped through from below
+    #[serde(default)]
+    pub one_way: bool,
+}
+
+/// An area effector (e.g. wind or a conveyor belt), acting on the moving entities inside
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EffectorData {
+    /// The position of the center of the area, in pixels
+    pub position: (f32, f32),
+    /// The size of the area, in pixels
+    pub size: (f32, f32),
+    pub effector: AreaEffector,
+}
+
+/// A volume of water, in which the entities float and swim
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WaterData {
+    /// The position of the center of the volume, in pixels
+    pub position: (f32, f32),
+    /// The size of the volume, in pixels
+    pub size: (f32, f32),
+    #[serde(default)]
+    pub water: Water,
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    pub elements: HashMap<(i32, i32), Block>,
+    /// The moving platforms, spawned with the map
+    pub moving_platforms: Vec<PlatformData>,
+    /// The trigger volumes, spawned with the map
+    pub triggers: Vec<TriggerData>,
+    /// The area effectors, spawned with the map
+    pub effectors: Vec<EffectorData>,
+    /// The volumes of water, spawned with the map
+    pub water: Vec<WaterData>,
+}
+
+/// The serialized representation of a map, on disk or sent to the clients
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MapData {
+    pub blocks: Vec<(i32, i32)>,
+    /// The one-way blocks
+    #[serde(default)]
+    pub platforms: Vec<(i32, i32)>,
+    #[serde(default)]
+    pub moving_platforms: Vec<PlatformData>,
+    #[serde(default)]
+    pub triggers: Vec<TriggerData>,
+    #[serde(default)]
+    pub effectors: Vec<EffectorData>,
+    #[serde(default)]
+    pub water: Vec<WaterData>,
+}
+
+/// The tile covering the given grid cell
+pub fn tile_at_cell(column: i32, row: i32) -> (i32, i32) {
+    (column + GRID_ORIGIN.0, GRID_ORIGIN.1 - row)
+}
+
+/// The grid cell covered by the given tile
+pub fn cell_of_tile((x, y): (i32, i32)) -> (i32, i32) {
+    (x - GRID_ORIGIN.0, GRID_ORIGIN.1 - y)
+}
+
+/// The grid cell containing the given coordinate, on a single axis
+pub fn cell_at(coordinate: f32) -> i32 {
+    (coordinate / TILE_SIZE).floor() as i32
+}
+
+/// The area covered by the given tile, in pixels
+pub fn tile_rect(tile: (i32, i32)) -> Rect {
+    let (column, row) = cell_of_tile(tile);
+
+    Rect::new(
+        column as f32 * TILE_SIZE,
+        row as f32 * TILE_SIZE,
+        TILE_SIZE,
+        TILE_SIZE,
+    )
+}
+
+impl Map {
+    /// The block in the given grid cell, if any
+    pub fn block_in_cell(&self, column: i32, row: i32) -> Option<&Block> {
+        self.elements.get(&tile_at_cell(column, row))
+    }
+
+    /// Loads a map from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let data: MapData = serde_json::from_reader(file)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, Box::new(err)))?;
+
+        Ok(Map::from(data))
+    }
+}
+
+impl From<MapData> for Map {
+    fn from(data: MapData) -> Self {
+        let blocks = data
+            .blocks
+            .into_iter()
+            .map(|p| (p, Block { one_way: false }));
+        let platforms = data
+            .platforms
+            .into_iter()
+            .map(|p| (p, Block { one_way: true }));
+
+        Map {
+            elements: blocks.chain(platforms).collect(),
+            moving_platforms: data.moving_platforms,
+            triggers: data.triggers,
+            effectors: data.effectors,
+            water: data.water,
+        }
+    }
+}
+
+impl<'a> From<&'a Map> for MapData {
+    fn from(map: &'a Map) -> Self {
+        // Sorted, for the same map to always give the same data
+        let mut blocks: Vec<(i32, i32)> = Vec::new();
+        let mut platforms: Vec<(i32, i32)> = Vec::new();
+        for (&tile, block) in &map.elements {
+            if block.one_way {
+                platforms.push(tile);
+            } else {
+                blocks.push(tile);
+            }
+        }
+        blocks.sort();
+        platforms.sort();
+
+        MapData {
+            blocks,
+            platforms,
+            moving_platforms: map.moving_platforms.clone(),
+            triggers: map.triggers.clone(),
+            effectors: map.effectors.clone(),
+            water: map.water.clone(),
+        }
+    }
+}
+
+impl Default for Map {
+    // debug map
+    fn default() -> Self {
+        Map {
+            elements: DEBUG_MAP.iter().map(|&p| (p, Block::default())).collect(),
+            moving_platforms: Vec::new(),
+            triggers: Vec::new(),
+            effectors: Vec::new(),
+            water: Vec::new(),
+        }
+    }
+}
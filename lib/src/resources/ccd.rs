@@ -0,0 +1,40 @@
+use nalgebra::Vector2;
+
+/// A resource for the SPECS world configuring the continuous collision detection
+///
+/// Moving entities going faster than the threshold are moved in several sub-steps,
+/// each one no longer than the threshold.
+pub struct CcdSettings {
+    /// The distance (in pixels per update) above which the motion is sub-stepped
+    pub substep_threshold: f32,
+    /// The maximum number of sub-steps for a single update
+    pub max_substeps: u32,
+}
+
+impl CcdSettings {
+    /// Creates the CcdSettings resource, with the default settings
+    pub fn new() -> Self {
+        CcdSettings {
+            substep_threshold: 16.,
+            max_substeps: 16,
+        }
+    }
+
+    /// The number of sub-steps needed for the given motion
+    pub fn substeps(&self, motion: Vector2<f32>) -> u32 {
+        let distance = motion.norm();
+
+        if self.substep_threshold <= 0. || distance <= self.substep_threshold {
+            return 1;
+        }
+
+        let substeps = (distance / self.substep_threshold).ceil() as u32;
+        substeps.min(self.max_substeps).max(1)
+    }
+}
+
+impl Default for CcdSettings {
+    fn default() -> Self {
+        CcdSettings::new()
+    }
+}
@@ -0,0 +1,38 @@
+use resources::events::Events;
+use specs::Entity;
+
+/// An event happening when two colliders start or stop touching
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollisionEvent {
+    /// The two entities started touching during the last update
+    Started(Entity, Entity),
+    /// The two entities stopped touching during the last update
+    Stopped(Entity, Entity),
+}
+
+impl CollisionEvent {
+    /// The two entities involved in the event
+    pub fn entities(&self) -> (Entity, Entity) {
+        match *self {
+            CollisionEvent::Started(a, b) | CollisionEvent::Stopped(a, b) => (a, b),
+        }
+    }
+
+    /// If `entity` is involved in the event, the other entity involved
+    pub fn other(&self, entity: Entity) -> Option<Entity> {
+        let (a, b) = self.entities();
+
+        if a == entity {
+            Some(b)
+        } else if b == entity {
+            Some(a)
+        } else {
+            None
+        }
+    }
+}
+
+/// A resource for the SPECS world holding the collision events of the last update
+///
+/// The events are replaced on each update of the collision world.
+pub type CollisionEvents = Events<CollisionEvent>;
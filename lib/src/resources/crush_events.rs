@@ -0,0 +1,16 @@
+use resources::events::Events;
+use specs::Entity;
+
+/// An event happening when a moving platform squeezes an entity against something solid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrushEvent {
+    /// The entity crushed
+    pub entity: Entity,
+    /// The platform crushing it
+    pub platform: Entity,
+}
+
+/// A resource for the SPECS world holding the crush events of the last update
+///
+/// The events are replaced on each update of the moving platforms.
+pub type CrushEvents = Events<CrushEvent>;
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use time::precise_time_ns;
 
 /// A resource for the SPECS world giving the delta time between two updates
@@ -17,11 +18,36 @@ impl DeltaTime {
         }
     }
 
-    /// Updates the DeltaTime resource
+    /// Creates a DeltaTime resource with a fixed delta time, in seconds
+    pub fn from_seconds(seconds: f32) -> Self {
+        let mut delta_time = DeltaTime::new();
+        delta_time.set_ns((seconds * 1_000_000_000.0) as u64);
+        delta_time
+    }
+
+    /// Updates the DeltaTime resource with the time elapsed since the last update
     pub fn update(&mut self) {
         let new_time: u64 = precise_time_ns();
-        self.delta_ns = new_time - self.old_time;
-        self.delta_ms = self.delta_ns as f32 / 1000.0;
+        let elapsed = new_time - self.old_time;
+        self.set_ns(elapsed);
+        self.old_time = new_time;
+    }
+
+    /// Updates the DeltaTime resource with a fixed delta time
+    pub fn set(&mut self, delta: Duration) {
+        self.set_ns(delta.as_secs() * 1_000_000_000 + u64::from(delta.subsec_nanos()));
+        self.old_time = precise_time_ns();
+    }
+
+    /// The delta time, in seconds
+    pub fn seconds(&self) -> f32 {
+        self.delta_ns as f32 / 1_000_000_000.0
+    }
+
+    // Sets the delta time, in nanoseconds
+    fn set_ns(&mut self, delta_ns: u64) {
+        self.delta_ns = delta_ns;
+        self.delta_ms = delta_ns as f32 / 1_000_000.0;
     }
 }
 
@@ -0,0 +1,55 @@
+use std::slice::Iter;
+
+/// A resource for the SPECS world holding the events of type `T` published during the last update
+///
+/// The events are replaced on each update, by the system publishing them.
+pub struct Events<T> {
+    events: Vec<T>,
+}
+
+impl<T> Events<T> {
+    /// Creates the resource, without any event
+    pub fn new() -> Self {
+        Events { events: Vec::new() }
+    }
+
+    /// Removes all the events
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Adds an event
+    pub fn push(&mut self, event: T) {
+        self.events.push(event);
+    }
+
+    /// The events of the last update
+    pub fn iter(&self) -> Iter<T> {
+        self.events.iter()
+    }
+
+    /// The number of events of the last update
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether there was no event during the last update
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Events::new()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Events<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter()
+    }
+}
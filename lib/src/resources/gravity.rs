@@ -0,0 +1,85 @@
+use components::controller::CharacterController;
+use components::moving::{GravityAffected, DEFAULT_GRAVITY};
+use nalgebra::Vector2;
+
+/// A resource for the SPECS world holding the world's gravity, in pixels per second squared
+///
+/// The entities with a `GravityAffected` component are pulled by it (see
+/// `GravityAffected::gravity`), except the ones with a `CharacterController`, which are pulled
+/// along it by their own (see `CharacterController::gravity`).
+pub struct Gravity {
+    /// The world's gravity
+    pub vector: Vector2<f32>,
+}
+
+impl Gravity {
+    /// Creates the Gravity resource, pulling down
+    pub fn new() -> Self {
+        Gravity::new_custom(0., DEFAULT_GRAVITY)
+    }
+
+    /// Creates a Gravity resource pulling along the given vector
+    pub fn new_custom(x: f32, y: f32) -> Self {
+        Gravity {
+            vector: Vector2::new(x, y),
+        }
+    }
+
+    /// The direction the world's gravity pulls towards (down when there is none)
+    pub fn direction(&self) -> Vector2<f32> {
+        if self.vector.norm() > 0. {
+            self.vector.normalize()
+        } else {
+            Vector2::new(0., 1.)
+        }
+    }
+
+    /// The direction an entity with the given `GravityAffected` component falls towards: its
+    /// ground is that way (the world's gravity direction, if it floats)
+    pub fn down(&self, affected: Option<&GravityAffected>) -> Vector2<f32> {
+        let down = affected.map_or(self.direction(), |affected| {
+            affected.gravity(self.direction())
+        });
+
+        if down.norm() > 0. {
+            down.normalize()
+        } else {
+            self.direction()
+        }
+    }
+
+    /// The direction an entity with the given `GravityAffected` component runs towards when
+    /// going right: along its ground (see `down`), the same way as the screen's right if it can
+    pub fn side(&self, affected: Option<&GravityAffected>) -> Vector2<f32> {
+        let down = self.down(affected);
+        let side = Vector2::new(down.y, -down.x);
+
+        if side.x < 0. || (side.x == 0. && side.y < 0.) {
+            -side
+        } else {
+            side
+        }
+    }
+
+    /// The gravity pulling an entity with the given components, nothing pulling the ones
+    /// neither affected by the gravity nor controlled
+    pub fn applied(
+        &self,
+        affected: Option<&GravityAffected>,
+        controller: Option<&CharacterController>,
+    ) -> Vector2<f32> {
+        let gravity = match (affected, controller) {
+            (_, Some(ctrl)) => self.direction() * ctrl.gravity(),
+            (Some(_), None) => self.vector,
+            (None, None) => return Vector2::new(0., 0.),
+        };
+
+        affected.map_or(gravity, |affected| affected.gravity(gravity))
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity::new()
+    }
+}
@@ -1 +1,8 @@
+pub mod ccd;
+pub mod collision_events;
+pub mod crush_events;
 pub mod delta_time;
+pub mod events;
+pub mod gravity;
+pub mod trigger_events;
+pub mod water_events;
@@ -0,0 +1,38 @@
+use resources::events::Events;
+use specs::Entity;
+
+/// An event happening when an entity overlaps a trigger
+///
+/// The first entity is always the trigger, the second one the entity overlapping it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerEvent {
+    /// The entity started overlapping the trigger during the last update
+    Enter(Entity, Entity),
+    /// The entity was already overlapping the trigger, and still is
+    Stay(Entity, Entity),
+    /// The entity stopped overlapping the trigger (or was despawned) during the last update
+    Exit(Entity, Entity),
+}
+
+impl TriggerEvent {
+    /// The trigger involved in the event
+    pub fn trigger(&self) -> Entity {
+        match *self {
+            TriggerEvent::Enter(trigger, _)
+            | TriggerEvent::Stay(trigger, _)
+            | TriggerEvent::Exit(trigger, _) => trigger,
+        }
+    }
+
+    /// The entity overlapping the trigger
+    pub fn entity(&self) -> Entity {
+        match *self {
+            TriggerEvent::Enter(_, entity)
+            | TriggerEvent::Stay(_, entity)
+            | TriggerEvent::Exit(_, entity) => entity,
+        }
+    }
+}
+
+/// A resource for the SPECS world holding the trigger events of the last update
+pub type TriggerEvents = Events<TriggerEvent>;
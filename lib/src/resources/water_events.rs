@@ -0,0 +1,39 @@
+use nalgebra::Vector2;
+use resources::events::Events;
+use specs::Entity;
+
+/// An event happening when an entity enters or exits the water, e.g. to make a splash
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaterEvent {
+    /// The entity touched the water
+    Enter(Splash),
+    /// The entity got out of the water
+    Exit(Splash),
+}
+
+/// Where and how an entity crossed the surface of the water
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Splash {
+    /// The water crossed
+    pub water: Entity,
+    /// The entity crossing it
+    pub entity: Entity,
+    /// Where the entity crossed the surface (its center, at the height of the surface)
+    pub position: Vector2<f32>,
+    /// The velocity of the entity when crossing the surface, in pixels per second
+    pub velocity: Vector2<f32>,
+}
+
+impl WaterEvent {
+    /// Where and how the surface was crossed
+    pub fn splash(&self) -> &Splash {
+        match *self {
+            WaterEvent::Enter(ref splash) | WaterEvent::Exit(ref splash) => splash,
+        }
+    }
+}
+
+/// A resource for the SPECS world holding the water events of the last update
+///
+/// The events are replaced on each update of the water.
+pub type WaterEvents = Events<WaterEvent>;
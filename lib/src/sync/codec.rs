@@ -18,6 +18,7 @@ pub struct Lines<S: Serialize, D: DeserializeOwned> {
     socket: TcpStream,
     rd: BytesMut,
     wr: BytesMut,
+    last_line_len: usize,
     serializer: PhantomData<S>,
     deserializer: PhantomData<D>,
 }
@@ -29,6 +30,7 @@ impl<S: Serialize, D: DeserializeOwned> Lines<S, D> {
             socket,
             rd: BytesMut::new(),
             wr: BytesMut::new(),
+            last_line_len: 0,
             serializer: PhantomData,
             deserializer: PhantomData,
         }
@@ -38,7 +40,13 @@ impl<S: Serialize, D: DeserializeOwned> Lines<S, D> {
         self.socket.peer_addr()
     }
 
-    pub fn buffer(&mut self, data: &S) -> Result<(), serde_json::Error> {
+    /// Size in bytes of the last line read (message + \r\n)
+    pub fn last_line_len(&self) -> usize {
+        self.last_line_len
+    }
+
+    /// Buffers a message, returns the size in bytes of the line (message + \r\n)
+    pub fn buffer(&mut self, data: &S) -> Result<usize, serde_json::Error> {
         let data = serde_json::to_vec(data)?;
 
         debug!("buffering {} bytes", data.len());
@@ -54,7 +62,7 @@ impl<S: Serialize, D: DeserializeOwned> Lines<S, D> {
         self.wr.put(data);
         self.wr.put("\r\n");
 
-        Ok(())
+        Ok(len)
     }
 
     pub fn poll_flush(&mut self) -> Poll<(), io::Error> {
@@ -119,6 +127,7 @@ impl<S: Serialize, D: DeserializeOwned> Stream for Lines<S, D> {
 
             // Drop the trailing \r\n
             line.split_off(pos);
+            self.last_line_len = pos + 2;
 
             let data: D = serde_json::from_slice(&line)
                 .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, Box::new(err)))?;
@@ -1,9 +1,13 @@
+use components::platform::PlatformState;
+use map::MapData;
 use std::time::SystemTime;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Client {
-    Test,             // An empty message, to test protocols
-    Ping(SystemTime), // Current time, to synchronize client and server
+    Test,                   // An empty message, to test protocols
+    Ping(SystemTime),       // Current time, to synchronize client and server
+    Hello { name: String }, // Sent on connection, to introduce the player
+    Pong(SystemTime),       // Answer to a server `Ping`, to measure the RTT
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -13,4 +17,42 @@ pub enum Server {
         client: SystemTime,
         server: SystemTime,
     },
+    Shutdown {
+        reason: String,
+    },
+    Ping(SystemTime), // Current time, answered with a `Pong` to measure the RTT
+    Kicked {
+        reason: String,
+    },
+    Announcement(String), // A message from the admin, to display to players
+    Map(MapData),         // The map played, sent on connection and whenever it changes
+    Platforms(Vec<PlatformState>), // Where the moving platforms are, sent regularly
+}
+
+impl Client {
+    /// The name of the message variant, for logs and metrics
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Client::Test => "Test",
+            Client::Ping(_) => "Ping",
+            Client::Hello { .. } => "Hello",
+            Client::Pong(_) => "Pong",
+        }
+    }
+}
+
+impl Server {
+    /// The name of the message variant, for logs and metrics
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Server::Test => "Test",
+            Server::Pong { .. } => "Pong",
+            Server::Shutdown { .. } => "Shutdown",
+            Server::Ping(_) => "Ping",
+            Server::Kicked { .. } => "Kicked",
+            Server::Announcement(_) => "Announcement",
+            Server::Map(_) => "Map",
+            Server::Platforms(_) => "Platforms",
+        }
+    }
 }
@@ -1,2 +1,12 @@
+pub mod sys_bodies;
 pub mod sys_colliding;
+pub mod sys_controller;
+pub mod sys_effectors;
+pub mod sys_gravity_zones;
+pub mod sys_grounded;
+pub mod sys_jump;
 pub mod sys_moving;
+pub mod sys_moving_collision;
+pub mod sys_platform;
+pub mod sys_trigger;
+pub mod sys_water;
@@ -0,0 +1,281 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::{CollisionLayer, InteractionMatrix};
+use collision::resolution::{bounce, penetration};
+use collision::statics::{self, StaticCollider};
+use collision::tiles;
+use components::collider::Collider;
+use components::moving::{GravityAffected, Moving};
+use components::rigid_body::RigidBody;
+use components::transform::Transform;
+use map::Map;
+use nalgebra::{Translation2, Vector2};
+use resources::gravity::Gravity;
+use specs::{Entities, Entity, Fetch, Join, ReadStorage, System, WriteStorage};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use types::Rect;
+
+// How many times the contacts are solved in a single update: the more, the faster stacks settle
+const SOLVER_ITERATIONS: usize = 4;
+
+// How much shorter than its share an entity can be pushed before being considered blocked,
+// in pixels
+const BLOCKED_TOLERANCE: f32 = 0.001;
+
+// A moving entity taking part in the contacts with the bodies
+struct Participant {
+    entity: Entity,
+    rect: Rect,
+    velocity: Vector2<f32>,
+    inverse_mass: f32,
+    restitution: f32,
+    friction: f32,
+    layer: CollisionLayer,
+    is_body: bool,
+    drop_through: bool,
+    // Where its gravity pulls it
+    down: Vector2<f32>,
+}
+
+// What the participants can't be pushed into
+struct Surroundings<'a> {
+    map: &'a Map,
+    statics: &'a [StaticCollider],
+    interactions: &'a InteractionMatrix,
+}
+
+// A system solving the contacts between the dynamic bodies and the other moving entities,
+// once they moved: the entities in contact are separated in proportion to their inverse mass
+// (without going into the terrain), then bounce back and rub against each other
+//
+// The pairs in contact are the ones found by the collision world, which must be updated
+// beforehand. They are solved in the same order on every peer (from the lowest entity up,
+// which also settles the stacks faster), so that the client and the server agree on the
+// outcome.
+pub struct SysBodies {}
+
+impl<'a> System<'a> for SysBodies {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, RigidBody>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, Map>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, Gravity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut transform,
+            mut moving,
+            collider,
+            bodies,
+            gravity_affected,
+            map,
+            handler,
+            gravity,
+        ) = data;
+        if (&bodies).join().next().is_none() {
+            return;
+        }
+
+        let statics = StaticCollider::collect(&transform, &collider, &moving);
+        let surroundings = Surroundings {
+            map: &map,
+            statics: &statics,
+            interactions: handler.interactions(),
+        };
+
+        let default_body = RigidBody::default();
+        let mut participants: Vec<Participant> = (&*entities, &transform, &moving, &collider)
+            .join()
+            .map(|(entity, tr, mov, col)| {
+                let body = bodies.get(entity);
+                let properties = body.unwrap_or(&default_body);
+
+                Participant {
+                    entity,
+                    rect: tr.aabb(),
+                    velocity: mov.velocity.vector,
+                    inverse_mass: properties.inverse_mass(),
+                    restitution: properties.restitution,
+                    friction: properties.friction,
+                    layer: col.layer,
+                    is_body: body.is_some(),
+                    drop_through: mov.drop_through,
+                    down: gravity.down(gravity_affected.get(entity)),
+                }
+            })
+            .collect();
+
+        participants.sort_by(solving_order);
+        let starts: Vec<Rect> = participants.iter().map(|p| p.rect).collect();
+
+        // The pairs of participants close enough to touch, in the solving order
+        let indices: HashMap<Entity, usize> = participants
+            .iter()
+            .enumerate()
+            .map(|(index, p)| (p.entity, index))
+            .collect();
+        let mut pairs: Vec<(usize, usize)> = handler
+            .world
+            .contact_pairs()
+            .filter_map(|(a, b, _)| {
+                let (a, b) = (*indices.get(a.data())?, *indices.get(b.data())?);
+                Some((a.min(b), a.max(b)))
+            })
+            .filter(|&(a, b)| {
+                let (a, b) = (&participants[a], &participants[b]);
+                (a.is_body || b.is_body) && surroundings.interactions.collides(a.layer, b.layer)
+            })
+            .collect();
+        pairs.sort_by_key(|&(a, b)| (b, a));
+        pairs.dedup();
+
+        for _ in 0..SOLVER_ITERATIONS {
+            for &(a, b) in &pairs {
+                let (head, tail) = participants.split_at_mut(b);
+                solve_contact(&mut head[a], &mut tail[0], &surroundings);
+            }
+        }
+
+        for (participant, start) in participants.iter().zip(starts) {
+            let moved = Vector2::new(participant.rect.x - start.x, participant.rect.y - start.y);
+
+            if let Some(tr) = transform.get_mut(participant.entity) {
+                tr.isometry
+                    .append_translation_mut(&Translation2::from_vector(moved));
+            }
+            if let Some(mov) = moving.get_mut(participant.entity) {
+                mov.velocity.vector = participant.velocity;
+            }
+        }
+    }
+}
+
+impl Participant {
+    // Moves by up to `motion`, stopping against the terrain, returning how far it moved
+    fn push(&mut self, motion: Vector2<f32>, surroundings: &Surroundings) -> Vector2<f32> {
+        if self.inverse_mass == 0. {
+            return Vector2::new(0., 0.);
+        }
+
+        let mut motion = motion;
+        if surroundings
+            .interactions
+            .collides(self.layer, CollisionLayer::Terrain)
+        {
+            motion = tiles::sweep(surroundings.map, &self.rect, motion, self.drop_through).motion;
+        }
+        let motion = statics::sweep(
+            surroundings.statics,
+            surroundings.interactions,
+            self.layer,
+            &self.rect,
+            motion,
+            self.down,
+            self.drop_through,
+        )
+        .motion;
+
+        self.rect.x += motion.x;
+        self.rect.y += motion.y;
+        motion
+    }
+}
+
+// The order the participants are solved in: from the bottom up, then from left to right,
+// then by the rest of what every peer knows of them (the ones alike in all of it being
+// interchangeable)
+fn solving_order(a: &Participant, b: &Participant) -> Ordering {
+    let keys = |p: &Participant| {
+        [
+            -(p.rect.y + p.rect.w),
+            p.rect.x,
+            p.rect.z,
+            p.rect.w,
+            p.velocity.x,
+            p.velocity.y,
+            p.inverse_mass,
+            p.restitution,
+            p.friction,
+            p.layer.group_id() as f32,
+            if p.is_body { 1. } else { 0. },
+            if p.drop_through { 1. } else { 0. },
+            p.down.x,
+            p.down.y,
+        ]
+    };
+
+    keys(a)
+        .iter()
+        .zip(keys(b).iter())
+        .map(|(a, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .find(|&order| order != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+// Separates two participants if they overlap, and changes their velocities so that they stop
+// moving into each other
+fn solve_contact(a: &mut Participant, b: &mut Participant, surroundings: &Surroundings) {
+    if a.inverse_mass + b.inverse_mass == 0. {
+        return;
+    }
+
+    // Pushes `a` out of `b`
+    let push = match penetration(&a.rect, &b.rect) {
+        Some(push) => push,
+        None => return,
+    };
+
+    // Each moves its share, and what one can't do (e.g. because of the ground below it)
+    // is done by the other
+    let a_share = push * (a.inverse_mass / (a.inverse_mass + b.inverse_mass));
+    let mut a_moved = a.push(a_share, surroundings);
+    let b_moved = -b.push(a_moved - push, surroundings);
+    a_moved += a.push(push - a_moved - b_moved, surroundings);
+
+    // What couldn't move its share is held in place (e.g. resting on the ground), as if it
+    // couldn't be pushed at all
+    let a_inverse_mass = if a_moved.norm() + BLOCKED_TOLERANCE < a_share.norm() {
+        0.
+    } else {
+        a.inverse_mass
+    };
+    let b_inverse_mass = if b_moved.norm() + BLOCKED_TOLERANCE < (push - a_share).norm() {
+        0.
+    } else {
+        b.inverse_mass
+    };
+    let total_inverse_mass = a_inverse_mass + b_inverse_mass;
+    if total_inverse_mass == 0. {
+        return;
+    }
+
+    // Only the velocities moving them into each other change
+    let normal = push.normalize();
+    let speed = (a.velocity - b.velocity).dot(&normal);
+    if speed >= 0. {
+        return;
+    }
+
+    let restitution = a.restitution.max(b.restitution);
+    let normal_impulse = (bounce(speed, restitution) - speed) / total_inverse_mass;
+    a.velocity += normal * (normal_impulse * a_inverse_mass);
+    b.velocity -= normal * (normal_impulse * b_inverse_mass);
+
+    // The friction slows down their sliding against each other, up to stopping it
+    let tangent = Vector2::new(-normal.y, normal.x);
+    let sliding = (a.velocity - b.velocity).dot(&tangent);
+    let max_friction = (a.friction * b.friction).sqrt() * normal_impulse;
+    let friction_impulse = (-sliding / total_inverse_mass)
+        .max(-max_friction)
+        .min(max_friction);
+    a.velocity += tangent * (friction_impulse * a_inverse_mass);
+    b.velocity -= tangent * (friction_impulse * b_inverse_mass);
+}
@@ -1,21 +1,32 @@
 use collision::collision_handling::CollisionHandler;
 use components::collider::Collider;
 use components::transform::Transform;
-use specs::{FetchMut, Join, ReadStorage, System};
+use ncollide::events::ContactEvent;
+use ncollide::world::{CollisionObjectHandle, CollisionWorld2};
+use resources::collision_events::{CollisionEvent, CollisionEvents};
+use specs::{Entities, Entity, FetchMut, Join, ReadStorage, System};
 
 pub struct SysCollide {}
 
 // A system updating the ncollide position of an item that has both moving and transform components,
-// thus allowing for collision detection with ncollide
+// thus allowing for collision detection with ncollide, and publishing the resulting contact events
 impl<'a> System<'a> for SysCollide {
     type SystemData = (
+        Entities<'a>,
         ReadStorage<'a, Transform>,
         ReadStorage<'a, Collider>,
         FetchMut<'a, CollisionHandler>,
+        FetchMut<'a, CollisionEvents>,
     );
 
     // Updates the CollisionHandler based on the transform's position
-    fn run(&mut self, (transform, collider, mut collision_handler): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, transform, collider, mut collision_handler, mut events): Self::SystemData,
+    ) {
+        // Forget about the despawned entities, they stopped touching anything
+        let despawned = collision_handler.remove_dead(&entities);
+
         for (tr, col) in (&transform, &collider).join() {
             collision_handler
                 .world
@@ -23,5 +34,38 @@ impl<'a> System<'a> for SysCollide {
         }
 
         collision_handler.world.update();
+
+        // Publish the contact events, tagged with the entities owning the colliders
+        events.clear();
+        for (a, b) in despawned {
+            events.push(CollisionEvent::Stopped(a, b));
+        }
+        let world = &collision_handler.world;
+        for event in world.contact_events() {
+            let event = match *event {
+                ContactEvent::Started(a, b) => {
+                    owners(world, a, b).map(|(a, b)| CollisionEvent::Started(a, b))
+                }
+                ContactEvent::Stopped(a, b) => {
+                    owners(world, a, b).map(|(a, b)| CollisionEvent::Stopped(a, b))
+                }
+            };
+
+            if let Some(event) = event {
+                events.push(event);
+            }
+        }
+    }
+}
+
+// The entities owning the two collision objects
+fn owners(
+    world: &CollisionWorld2<f32, Entity>,
+    a: CollisionObjectHandle,
+    b: CollisionObjectHandle,
+) -> Option<(Entity, Entity)> {
+    match (world.collision_object(a), world.collision_object(b)) {
+        (Some(a), Some(b)) => Some((*a.data(), *b.data())),
+        _ => None,
     }
 }
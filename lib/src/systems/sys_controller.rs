@@ -0,0 +1,236 @@
+use collision::resolution::projection;
+use components::climbable::Climbable;
+use components::controller::CharacterController;
+use components::grounded::Grounded;
+use components::input::Input;
+use components::jump::Jump;
+use components::moving::{GravityAffected, Moving};
+use components::transform::Transform;
+use components::trigger::Trigger;
+use components::water::{submerged, Water};
+use nalgebra::Vector2;
+use resources::delta_time::DeltaTime;
+use resources::gravity::Gravity;
+use specs::{Entities, Entity, Fetch, Join, ReadStorage, System, WriteStorage};
+use types::Rect;
+
+// How deep in the water an entity starts swimming, as the fraction of it submerged
+const SWIM_DEPTH: f32 = 0.3;
+
+// A system moving the entities with a character controller, from their input:
+// running, jumping (off the ground or a wall), sliding along walls, dashing, climbing
+// and swimming
+pub struct SysController {}
+
+impl<'a> System<'a> for SysController {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Input>,
+        WriteStorage<'a, CharacterController>,
+        WriteStorage<'a, Moving>,
+        WriteStorage<'a, Jump>,
+        ReadStorage<'a, Grounded>,
+        ReadStorage<'a, GravityAffected>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Trigger>,
+        ReadStorage<'a, Climbable>,
+        ReadStorage<'a, Water>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            input,
+            mut controller,
+            mut moving,
+            mut jump,
+            grounded,
+            gravity_affected,
+            transform,
+            triggers,
+            climbables,
+            waters,
+            world_gravity,
+            delta,
+        ) = data;
+        let dt = delta.seconds();
+
+        let areas: Vec<(Entity, Rect, Climbable)> = (&*entities, &transform, &climbables)
+            .join()
+            .map(|(zone, tr, climbable)| (zone, tr.aabb(), *climbable))
+            .collect();
+        let volumes: Vec<(Entity, Rect)> = (&*entities, &transform, &waters)
+            .join()
+            .map(|(zone, tr, _)| (zone, tr.aabb()))
+            .collect();
+
+        for (entity, input, ctrl, mov, jump, ground) in (
+            &*entities,
+            &input,
+            &mut controller,
+            &mut moving,
+            &mut jump,
+            &grounded,
+        )
+            .join()
+        {
+            let previous = ctrl.remember(*input);
+            mov.drop_through = input.down;
+            let velocity = &mut mov.velocity.vector;
+
+            // Dashing overrides everything else, even the gravity
+            let dash_pressed = input.dash && !previous.dash;
+            if let Some(dash) = ctrl.update_dash(*input, dash_pressed, ground.on_ground, dt) {
+                *velocity = dash;
+                ctrl.set_climbing(false);
+                continue;
+            }
+            let direction = input.horizontal();
+
+            // Its own gravity along the world's, changed like it by its `GravityAffected`
+            // component: its ground is where it pulls it
+            let affected = gravity_affected.get(entity);
+            let gravity = world_gravity.applied(affected, Some(&*ctrl));
+            let down = world_gravity.down(affected);
+            let side = world_gravity.side(affected);
+
+            // Grab what's climbable going up (unless already above it, or just climbed out of
+            // it with up still held) or down in the air, then climb it without gravity
+            let overlaps = |zone: Entity| {
+                triggers
+                    .get(zone)
+                    .map_or(false, |trigger| trigger.is_overlapped_by(entity))
+            };
+            // The speed to move by the given distance within the update
+            let reach = |distance: f32| if dt > 0. { distance / dt } else { 0. };
+            if !input.up {
+                ctrl.set_topped_out(false);
+            }
+            let area = areas.iter().find(|&&(zone, _, _)| overlaps(zone));
+            if let (Some(&(_, area, climbable)), Some(tr)) = (area, transform.get(entity)) {
+                // Up and down are along its gravity, like its top and bottom
+                let position = tr.isometry.translation.vector;
+                let bottom = projection(&tr.as_rect(), down).1;
+                let top = projection(&area, down).0;
+                let grabs_up = input.up && !ctrl.topped_out() && bottom > top;
+                if grabs_up || (input.down && !ground.on_ground) {
+                    ctrl.set_climbing(true);
+                    jump.cancel();
+                }
+
+                if ctrl.is_climbing() && input.jump && !previous.jump {
+                    // Jump off, towards the direction held
+                    ctrl.set_climbing(false);
+                    *velocity = side * (direction * ctrl.run_speed) - down * ctrl.jump_speed();
+                    continue;
+                } else if ctrl.is_climbing() && input.down && ground.on_ground {
+                    // Let go once at the bottom
+                    ctrl.set_climbing(false);
+                } else if ctrl.is_climbing() && ctrl.topped_out() {
+                    // Made it to the top, where it lets go
+                    ctrl.set_climbing(false);
+                    *velocity = Vector2::new(0., 0.);
+                    continue;
+                } else if ctrl.is_climbing() {
+                    // Moved like the rest, stopped by what's in the way: the snapping included
+                    let mut fall = input.vertical() * ctrl.climb_speed;
+                    let run = if climbable.snap {
+                        let center = Vector2::new(area.x + area.z / 2., area.y + area.w / 2.);
+                        reach((center - position).dot(&side))
+                    } else {
+                        direction * ctrl.climb_speed
+                    };
+
+                    // Climbing past the top brings the entity up to it, then on it
+                    if fall < 0. && bottom + fall * dt <= top {
+                        fall = reach(top - bottom);
+                        ctrl.set_topped_out(true);
+                    }
+                    *velocity = side * run + down * fall;
+                    continue;
+                }
+            } else {
+                ctrl.set_climbing(false);
+            }
+
+            // Swim towards the direction held, the water keeping the entity afloat, and
+            // jump out once at the surface
+            let volume = volumes.iter().find(|&&(zone, _)| overlaps(zone));
+            let depth = match (volume, transform.get(entity)) {
+                (Some(&(_, volume)), Some(tr)) => submerged(&tr.aabb(), &volume),
+                _ => 0.,
+            };
+            ctrl.set_swimming(depth >= SWIM_DEPTH);
+            if ctrl.is_swimming() {
+                jump.cancel();
+                let target = Vector2::new(direction, input.vertical()) * ctrl.swim_speed;
+                let rate = ctrl.swim_acceleration * dt;
+                velocity.x += (target.x - velocity.x).max(-rate).min(rate);
+                if target.y != 0. {
+                    velocity.y += (target.y - velocity.y).max(-rate).min(rate);
+                }
+
+                if input.jump && !previous.jump {
+                    let speed = if depth < 1. {
+                        ctrl.jump_speed()
+                    } else {
+                        ctrl.swim_speed
+                    };
+                    *velocity = side * velocity.dot(&side) - down * speed;
+                }
+
+                mov.acceleration += gravity;
+                continue;
+            }
+
+            // How fast it runs along its ground, and rises away from it
+            let mut run = velocity.dot(&side);
+            let mut rise = -velocity.dot(&down);
+
+            // Run towards the direction held, or stop
+            let target = direction * ctrl.run_speed;
+            let rate = if direction != 0. {
+                ctrl.run_acceleration
+            } else {
+                ctrl.run_deceleration
+            };
+            run += (target - run).max(-rate * dt).min(rate * dt);
+
+            // Jump off the ground (within the coyote time), or off a wall
+            if input.jump && !previous.jump {
+                jump.press();
+            }
+            jump.speed = ctrl.jump_speed();
+
+            if jump.update(ground.on_ground, ground.time_since_grounded, dt) {
+                rise = jump.speed;
+            } else if jump.is_buffered() && !ground.on_ground && ground.on_wall() {
+                let away = if ground.on_wall_left { 1. } else { -1. };
+                run = away * ctrl.wall_jump_speed.x;
+                rise = ctrl.wall_jump_speed.y;
+                jump.cancel();
+            }
+
+            // Releasing the button early makes a shorter jump
+            if !input.jump && previous.jump && rise > 0. {
+                rise *= ctrl.jump_cut;
+            }
+
+            // Slide slowly along the wall held against
+            let against_wall =
+                (ground.on_wall_left && direction < 0.) || (ground.on_wall_right && direction > 0.);
+            let sliding = against_wall && !ground.on_ground && -rise >= ctrl.wall_slide_speed;
+            if sliding {
+                rise = -ctrl.wall_slide_speed;
+            }
+
+            *velocity = side * run - down * rise;
+            if !sliding {
+                mov.acceleration += gravity;
+            }
+        }
+    }
+}
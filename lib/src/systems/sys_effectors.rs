@@ -0,0 +1,146 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use collision::statics::{self, StaticCollider};
+use collision::tiles;
+use components::collider::Collider;
+use components::effector::AreaEffector;
+use components::grounded::Grounded;
+use components::moving::{GravityAffected, Moving};
+use components::transform::Transform;
+use components::trigger::Trigger;
+use map::Map;
+use nalgebra::Translation2;
+use resources::delta_time::DeltaTime;
+use resources::gravity::Gravity;
+use resources::trigger_events::{TriggerEvent, TriggerEvents};
+use specs::{Entities, Fetch, Join, ReadStorage, System, WriteStorage};
+
+// A system applying the area effectors to the moving entities overlapping their trigger:
+// the forces to the ones inside, the impulses to the ones that just entered, and carrying
+// the ones standing on the conveyors (along their ground, which their gravity decides)
+//
+// The forces and impulses are only added to the entities: they are applied when they move.
+pub struct SysEffectors {}
+
+impl<'a> System<'a> for SysEffectors {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, Grounded>,
+        ReadStorage<'a, Trigger>,
+        ReadStorage<'a, AreaEffector>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, TriggerEvents>,
+        Fetch<'a, Map>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut transform,
+            mut moving,
+            collider,
+            grounded,
+            triggers,
+            effectors,
+            gravity_affected,
+            events,
+            map,
+            handler,
+            gravity,
+            delta,
+        ) = data;
+        let static_colliders = StaticCollider::collect(&transform, &collider, &moving);
+        let interactions = handler.interactions();
+
+        for (trigger, effector) in (&triggers, &effectors).join() {
+            for &entity in trigger.overlapping() {
+                let mov = match moving.get_mut(entity) {
+                    Some(mov) => mov,
+                    None => continue,
+                };
+
+                if let Some(force) = effector.force() {
+                    mov.add_force(force);
+                }
+
+                let speed = match *effector {
+                    AreaEffector::Conveyor { speed } => speed,
+                    _ => continue,
+                };
+                if !grounded.get(entity).map_or(false, |g| g.on_ground) {
+                    continue;
+                }
+                let (tr, col) = match (transform.get_mut(entity), collider.get(entity)) {
+                    (Some(tr), Some(col)) => (tr, col),
+                    _ => continue,
+                };
+
+                // Carried like by a moving platform, stopped by what's in the way
+                let affected = gravity_affected.get(entity);
+                let down = gravity.down(affected);
+                let mut carry = gravity.side(affected) * speed * delta.seconds();
+                if interactions.collides(col.layer, CollisionLayer::Terrain) {
+                    carry = tiles::sweep(&map, &tr.aabb(), carry, mov.drop_through).motion;
+                }
+                let carry = statics::sweep(
+                    &static_colliders,
+                    interactions,
+                    col.layer,
+                    &tr.aabb(),
+                    carry,
+                    down,
+                    mov.drop_through,
+                )
+                .motion;
+                tr.isometry
+                    .append_translation_mut(&Translation2::from_vector(carry));
+            }
+        }
+
+        for event in events.iter() {
+            let (zone, entity) = match *event {
+                TriggerEvent::Enter(zone, entity) => (zone, entity),
+                _ => continue,
+            };
+            let effector = match effectors.get(zone) {
+                Some(effector) => effector,
+                None => continue,
+            };
+            if !entities.is_alive(entity) {
+                continue;
+            }
+
+            let offset = match (transform.get(zone), transform.get(entity)) {
+                (Some(zone), Some(entity)) => {
+                    entity.isometry.translation.vector - zone.isometry.translation.vector
+                }
+                _ => continue,
+            };
+            let (mov, impulse) = match (moving.get_mut(entity), effector.impulse(offset)) {
+                (Some(mov), Some(impulse)) => (mov, impulse),
+                _ => continue,
+            };
+
+            // A bounce pad launches at the same speed, however the entity landed on it
+            match *effector {
+                AreaEffector::BouncePad { .. } if impulse.norm() > 0. => {
+                    let direction = impulse.normalize();
+                    let against = mov.velocity.vector.dot(&direction);
+                    if against < 0. {
+                        mov.velocity.vector -= direction * against;
+                    }
+                }
+                _ => {}
+            }
+
+            mov.add_impulse(impulse);
+        }
+    }
+}
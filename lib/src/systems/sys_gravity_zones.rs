@@ -0,0 +1,32 @@
+use components::gravity_zone::GravityZone;
+use components::moving::GravityAffected;
+use components::trigger::Trigger;
+use specs::{Entities, Join, ReadStorage, System, WriteStorage};
+
+// A system putting the entities affected by the gravity in the gravity zone whose trigger
+// they overlap (the one of highest priority, if they overlap several)
+pub struct SysGravityZones {}
+
+impl<'a> System<'a> for SysGravityZones {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Trigger>,
+        ReadStorage<'a, GravityZone>,
+        WriteStorage<'a, GravityAffected>,
+    );
+
+    fn run(&mut self, (entities, triggers, zones, mut gravity_affected): Self::SystemData) {
+        for (entity, affected) in (&*entities, &mut gravity_affected).join() {
+            let mut inside: Option<GravityZone> = None;
+
+            for (trigger, zone) in (&triggers, &zones).join() {
+                let higher = inside.map_or(true, |inside| zone.priority > inside.priority);
+                if higher && trigger.is_overlapped_by(entity) {
+                    inside = Some(*zone);
+                }
+            }
+
+            affected.set_zone(inside);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use collision::statics::{self, StaticCollider};
+use collision::tiles;
+use components::collider::Collider;
+use components::grounded::Grounded;
+use components::moving::{GravityAffected, Moving};
+use components::rigid_body::RigidBody;
+use components::transform::Transform;
+use map::Map;
+use nalgebra::Vector2;
+use resources::delta_time::DeltaTime;
+use resources::gravity::Gravity;
+use specs::{Entities, Entity, Fetch, Join, ReadStorage, System, WriteStorage};
+
+// How far from the entity the ground and the walls are looked for, in pixels
+const PROBE_DISTANCE: f32 = 1.;
+
+// A system updating what the moving entities are touching, once their collisions are resolved:
+// they are on the ground (or against a wall) if moving slightly towards where their gravity
+// pulls them (or sideways) is blocked (by the terrain, or by a dynamic body)
+pub struct SysGrounded {}
+
+impl<'a> System<'a> for SysGrounded {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Moving>,
+        ReadStorage<'a, Collider>,
+        WriteStorage<'a, Grounded>,
+        ReadStorage<'a, RigidBody>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, Map>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            transform,
+            moving,
+            collider,
+            mut grounded,
+            bodies,
+            gravity_affected,
+            map,
+            handler,
+            gravity,
+            delta_time,
+        ) = data;
+        let static_colliders = StaticCollider::collect(&transform, &collider, &moving);
+        let interactions = handler.interactions();
+
+        // The dynamic bodies can be stood on, like the static colliders
+        let solid_bodies: Vec<(Entity, StaticCollider)> =
+            (&*entities, &transform, &collider, &bodies)
+                .join()
+                .map(|(entity, tr, col, _)| {
+                    let body = StaticCollider {
+                        rect: tr.aabb(),
+                        shape: tr.oriented_rect(),
+                        layer: col.layer,
+                        one_way: false,
+                    };
+                    (entity, body)
+                })
+                .collect();
+
+        for (entity, tr, mov, col, ground) in
+            (&*entities, &transform, &moving, &collider, &mut grounded).join()
+        {
+            let rect = tr.aabb();
+            let other_bodies: Vec<StaticCollider> = solid_bodies
+                .iter()
+                .filter(|&&(other, _)| other != entity)
+                .map(|&(_, body)| body)
+                .collect();
+            let hits_tiles = interactions.collides(col.layer, CollisionLayer::Terrain);
+            let down = gravity.down(gravity_affected.get(entity));
+
+            // Whether moving by the probe is blocked by the tiles, the static colliders
+            // or the other bodies
+            let blocked = |probe: Vector2<f32>| {
+                let by_tiles = hits_tiles && {
+                    let sweep = tiles::sweep(&map, &rect, probe, mov.drop_through);
+                    sweep.blocked_x || sweep.blocked_y
+                };
+
+                let by_colliders = |colliders: &[StaticCollider]| {
+                    let sweep = statics::sweep(
+                        colliders,
+                        interactions,
+                        col.layer,
+                        &rect,
+                        probe,
+                        down,
+                        mov.drop_through,
+                    );
+                    sweep.blocked_x || sweep.blocked_y
+                };
+
+                by_tiles || by_colliders(&static_colliders) || by_colliders(&other_bodies)
+            };
+
+            // The ground is where its gravity pulls it, the walls on its sides (which may be
+            // the ceiling and the floor, when it is pulled sideways)
+            let side = gravity.side(gravity_affected.get(entity));
+            ground.on_ground = blocked(down * PROBE_DISTANCE);
+            ground.on_wall_left = blocked(-side * PROBE_DISTANCE);
+            ground.on_wall_right = blocked(side * PROBE_DISTANCE);
+
+            if ground.on_ground {
+                ground.time_since_grounded = 0.;
+            } else {
+                ground.time_since_grounded += delta_time.seconds();
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use components::controller::CharacterController;
+use components::grounded::Grounded;
+use components::jump::Jump;
+use components::moving::{GravityAffected, Moving};
+use resources::delta_time::DeltaTime;
+use resources::gravity::Gravity;
+use specs::{Entities, Fetch, Join, ReadStorage, System, WriteStorage};
+
+// A system making the entities jump when asked to, if they are (or just were) on the ground,
+// away from where their gravity pulls them (the entities with a character controller jump on
+// their own)
+pub struct SysJump {}
+
+impl<'a> System<'a> for SysJump {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Jump>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, Grounded>,
+        ReadStorage<'a, CharacterController>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut jump,
+            mut moving,
+            grounded,
+            controller,
+            gravity_affected,
+            gravity,
+            delta_time,
+        ) = data;
+
+        for (entity, jump, mov, ground, _) in
+            (&*entities, &mut jump, &mut moving, &grounded, !&controller).join()
+        {
+            let elapsed = delta_time.seconds();
+
+            if jump.update(ground.on_ground, ground.time_since_grounded, elapsed) {
+                let down = gravity.down(gravity_affected.get(entity));
+                let side = gravity.side(gravity_affected.get(entity));
+                let velocity = &mut mov.velocity.vector;
+                *velocity = side * velocity.dot(&side) - down * jump.speed;
+            }
+        }
+    }
+}
@@ -1,62 +1,182 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::CollisionLayer;
+use collision::resolution::bounce;
+use collision::statics::{self, StaticCollider};
+use collision::tiles::{self, Sweep};
+use components::collider::Collider;
+use components::controller::CharacterController;
+use components::grounded::Grounded;
 use components::moving::{GravityAffected, Moving};
+use components::rigid_body::RigidBody;
 use components::transform::Transform;
+use map::Map;
 use nalgebra::{Translation2, Vector2};
+use resources::ccd::CcdSettings;
 use resources::delta_time::DeltaTime;
-use specs::{Fetch, Join, ReadStorage, System, WriteStorage};
+use resources::gravity::Gravity;
+use specs::{Entities, Fetch, Join, ReadStorage, System, WriteStorage};
 
-// A system updating the transform of a moving entity
+// A system integrating the velocity of a moving entity, and updating its transform,
+// stopping it against the map's tiles and the static colliders on its way
+// (fast entities are moved in several sub-steps), and bouncing the bodies back
+//
+// The forces and impulses are applied first, according to the mass of the entity. The moving
+// entities collide as their axis-aligned bounds (see `Transform::aabb`), even when turned.
 pub struct SysMoving {}
 
 impl<'a> System<'a> for SysMoving {
-    type SystemData = (WriteStorage<'a, Transform>, ReadStorage<'a, Moving>);
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, Grounded>,
+        ReadStorage<'a, RigidBody>,
+        ReadStorage<'a, GravityAffected>,
+        ReadStorage<'a, CharacterController>,
+        Fetch<'a, Map>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, CcdSettings>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+    );
 
     // Applies the system to change transform components from moving objects
-    fn run(&mut self, (mut transform, moving): Self::SystemData) {
-        for (tr, mov) in (&mut transform, &moving).join() {
-            // Change the position of the transform
-            tr.isometry
-                .append_translation_mut(&Translation2::from_vector(mov.velocity.vector));
-        }
-    }
-}
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut transform,
+            mut moving,
+            collider,
+            grounded,
+            bodies,
+            gravity_affected,
+            controllers,
+            map,
+            handler,
+            ccd,
+            gravity,
+            delta,
+        ) = data;
+        let static_colliders = StaticCollider::collect(&transform, &collider, &moving);
+        let interactions = handler.interactions();
+        let dt = delta.seconds();
+        let default_body = RigidBody::default();
 
-// A system updating the moving component of an entity affected by gravity
-pub struct SysMovingGravity {
-    gravity_vec: Vector2<f32>,
-}
+        for (entity, tr, mov) in (&*entities, &mut transform, &mut moving).join() {
+            // Remember where it was, to know where it came from when it collides
+            mov.previous_position = Some(tr.isometry.translation.vector);
+
+            let body = bodies.get(entity).unwrap_or(&default_body);
+            mov.apply_forces(body.inverse_mass());
+
+            // The bodies rub against the ground as hard as their gravity presses them on it
+            if bodies.get(entity).is_some() {
+                let pressing =
+                    gravity.applied(gravity_affected.get(entity), controllers.get(entity));
+                mov.friction = body.friction * pressing.norm();
+            }
 
-impl SysMovingGravity {
-    pub fn new() -> Self {
-        SysMovingGravity {
-            gravity_vec: Vector2::new(0., 9.81),
+            // Where it would go if nothing blocked it
+            let on_ground = grounded
+                .get(entity)
+                .map_or(false, |ground| ground.on_ground);
+            let down = gravity.down(gravity_affected.get(entity));
+            let motion = mov.integrate(dt, on_ground, down);
+
+            // Entities without collider go through everything
+            let layer = match collider.get(entity) {
+                Some(col) => col.layer,
+                None => {
+                    tr.isometry
+                        .append_translation_mut(&Translation2::from_vector(motion));
+                    continue;
+                }
+            };
+            let hits_tiles = interactions.collides(layer, CollisionLayer::Terrain);
+            let restitution = body.restitution;
+
+            let substeps = ccd.substeps(motion);
+            let mut step = motion / substeps as f32;
+
+            for _ in 0..substeps {
+                let mut motion = step;
+
+                // Stop against the tiles...
+                if hits_tiles {
+                    let sweep = tiles::sweep(&map, &tr.aabb(), motion, mov.drop_through);
+                    motion = sweep.motion;
+                    stop_blocked(&sweep, &mut step, &mut mov.velocity.vector, restitution);
+                }
+
+                // ... and against the static colliders
+                let sweep = statics::sweep(
+                    &static_colliders,
+                    interactions,
+                    layer,
+                    &tr.aabb(),
+                    motion,
+                    down,
+                    mov.drop_through,
+                );
+                stop_blocked(&sweep, &mut step, &mut mov.velocity.vector, restitution);
+
+                // Change the position of the transform
+                tr.isometry
+                    .append_translation_mut(&Translation2::from_vector(sweep.motion));
+            }
         }
     }
+}
 
-    pub fn new_custom(x: f32, y: f32) -> Self {
-        SysMovingGravity {
-            gravity_vec: Vector2::new(x, y),
+// Stops the motion of the next sub-steps along the blocked axes, and the velocity
+// (bouncing it back by the restitution)
+//
+// Against a turned side, only what goes into it stops: the rest slides along it.
+fn stop_blocked(
+    sweep: &Sweep,
+    step: &mut Vector2<f32>,
+    velocity: &mut Vector2<f32>,
+    restitution: f32,
+) {
+    if let Some(normal) = sweep.normal {
+        *step -= normal * step.dot(&normal).min(0.);
+        let into = velocity.dot(&normal);
+        if into < 0. {
+            *velocity += normal * (bounce(into, restitution) - into);
         }
+        return;
     }
-}
 
-impl Default for SysMovingGravity {
-    fn default() -> Self {
-        SysMovingGravity::new()
+    if sweep.blocked_x {
+        step.x = 0.;
+        velocity.x = bounce(velocity.x, restitution);
+    }
+    if sweep.blocked_y {
+        step.y = 0.;
+        velocity.y = bounce(velocity.y, restitution);
     }
 }
 
+// A system accelerating the entities affected by gravity (in pixels per second squared),
+// each by its own gravity (see `GravityAffected::gravity`) given the world's (see `Gravity`)
+//
+// The entities with a character controller are left to it.
+pub struct SysMovingGravity {}
+
 impl<'a> System<'a> for SysMovingGravity {
     type SystemData = (
         WriteStorage<'a, Moving>,
         ReadStorage<'a, GravityAffected>,
-        Fetch<'a, DeltaTime>,
+        ReadStorage<'a, CharacterController>,
+        Fetch<'a, Gravity>,
     );
 
-    fn run(&mut self, (mut moving, gravity_affected, delta_time): Self::SystemData) {
-        for (mov, _gravity) in (&mut moving, &gravity_affected).join() {
-            // Change the velocity of the moving object
-            mov.velocity.vector.x += self.gravity_vec.x;
-            mov.velocity.vector.y += self.gravity_vec.y * delta_time.delta_ms;
+    fn run(&mut self, (mut moving, gravity_affected, controllers, gravity): Self::SystemData) {
+        for (mov, affected, _) in (&mut moving, &gravity_affected, !&controllers).join() {
+            // The velocity is changed when the entity moves, over the update's delta time
+            mov.acceleration += affected.gravity(gravity.vector);
         }
     }
 }
@@ -0,0 +1,117 @@
+use collision::collision_handling::CollisionHandler;
+use collision::oriented::{self, OrientedRect};
+use collision::resolution::{overlap_area, penetration, penetration_from_above, projection};
+use collision::statics::StaticCollider;
+use components::collider::Collider;
+use components::moving::{GravityAffected, Moving};
+use components::transform::Transform;
+use nalgebra::{Translation2, Vector2};
+use resources::gravity::Gravity;
+use specs::{Entities, Fetch, Join, ReadStorage, System, WriteStorage};
+
+// Maximum number of static colliders a moving entity can be pushed out of in a single update
+const MAX_RESOLUTION_STEPS: usize = 8;
+
+// A system separating the moving entities from the static colliders they went into,
+// along the axis of least penetration, and stopping them along that axis
+//
+// The turned colliders push the entities out along the normal of their side instead. Like when
+// they move, the entities are their axis-aligned bounds (see `Transform::aabb`), even when
+// turned.
+pub struct SysMovingCollision {}
+
+impl<'a> System<'a> for SysMovingCollision {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, Gravity>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut transform, mut moving, collider, gravity_affected, handler, gravity) =
+            data;
+        // The static colliders are the ones that don't move
+        let statics = StaticCollider::collect(&transform, &collider, &moving);
+        let interactions = handler.interactions();
+
+        for (entity, tr, mov, col) in (&*entities, &mut transform, &mut moving, &collider).join() {
+            // How far the bottom of the entity reached before moving, along its gravity
+            let down = gravity.down(gravity_affected.get(entity));
+            let mut previous = tr.aabb();
+            if let Some(position) = mov.previous_position {
+                let moved = tr.isometry.translation.vector - position;
+                previous.x -= moved.x;
+                previous.y -= moved.y;
+            }
+            let previous_bottom = projection(&previous, down).1;
+
+            // Only the colliders blocking this one matter: one-way platforms only block
+            // the entities coming from above, unless they are dropping through
+            let blocking: Vec<&StaticCollider> = statics
+                .iter()
+                .filter(|other| {
+                    other.blocks(
+                        interactions,
+                        col.layer,
+                        previous_bottom,
+                        down,
+                        mov.drop_through,
+                    )
+                })
+                .collect();
+
+            for _ in 0..MAX_RESOLUTION_STEPS {
+                let rect = tr.aabb();
+
+                // Resolve the deepest overlap first: when standing across two blocks,
+                // this avoids being pushed sideways by the seam between them
+                let mut deepest: Option<Vector2<f32>> = None;
+                let mut deepest_area = 0.;
+                for &other in &blocking {
+                    let area = overlap_area(&rect, &other.rect);
+                    if area <= deepest_area {
+                        continue;
+                    }
+
+                    let push = if !other.shape.is_axis_aligned() {
+                        match oriented::penetration(&OrientedRect::from_rect(&rect), &other.shape) {
+                            // Only pushed up by a turned one-way platform
+                            Some(push) if other.one_way && push.dot(&down) >= 0. => None,
+                            push => push,
+                        }
+                    } else if other.one_way {
+                        penetration_from_above(&rect, &other.rect, down)
+                    } else {
+                        penetration(&rect, &other.rect)
+                    };
+                    if push.is_some() {
+                        deepest = push;
+                        deepest_area = area;
+                    }
+                }
+
+                let push = match deepest {
+                    Some(push) => push,
+                    None => break,
+                };
+
+                tr.isometry
+                    .append_translation_mut(&Translation2::from_vector(push));
+
+                // Stop the entity along the blocked direction, if it was moving into
+                // the collider
+                let normal = push.normalize();
+                let velocity = &mut mov.velocity.vector;
+                let into = velocity.dot(&normal);
+                if into < 0. {
+                    *velocity -= normal * into;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use collision::collision_handling::CollisionHandler;
+use collision::layers::{CollisionLayer, InteractionMatrix};
+use collision::resolution::{penetration, projection};
+use collision::statics::{self, StaticCollider};
+use collision::tiles;
+use components::collider::Collider;
+use components::moving::{GravityAffected, Moving};
+use components::platform::MovingPlatform;
+use components::transform::Transform;
+use map::Map;
+use nalgebra::{Translation2, Vector2};
+use resources::crush_events::{CrushEvent, CrushEvents};
+use resources::delta_time::DeltaTime;
+use resources::gravity::Gravity;
+use specs::{Entities, Entity, Fetch, FetchMut, Join, ReadStorage, System, WriteStorage};
+use types::Rect;
+
+// How far above a platform an entity can be and still ride it, in pixels
+const RIDE_TOLERANCE: f32 = 1.;
+
+// How deep into something solid a pushed entity has to be to be crushed, in pixels
+const CRUSH_TOLERANCE: f32 = 0.01;
+
+// A platform's move during an update
+struct PlatformMove {
+    entity: Entity,
+    // Where it was before moving
+    rect: Rect,
+    motion: Vector2<f32>,
+    layer: CollisionLayer,
+    one_way: bool,
+}
+
+// A system moving the platforms along their path, carrying the moving entities standing on
+// them, and pushing the ones in their way (reporting those pushed into something solid)
+pub struct SysPlatforms {}
+
+impl<'a> System<'a> for SysPlatforms {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        WriteStorage<'a, MovingPlatform>,
+        ReadStorage<'a, Collider>,
+        ReadStorage<'a, GravityAffected>,
+        Fetch<'a, Map>,
+        Fetch<'a, CollisionHandler>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, DeltaTime>,
+        FetchMut<'a, CrushEvents>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut transform,
+            mut moving,
+            mut platforms,
+            collider,
+            gravity_affected,
+            map,
+            handler,
+            gravity,
+            delta,
+            mut events,
+        ) = data;
+        let interactions = handler.interactions();
+        events.clear();
+
+        let mut moves = Vec::new();
+        for (entity, platform, tr, col) in
+            (&*entities, &mut platforms, &mut transform, &collider).join()
+        {
+            let rect = tr.aabb();
+            let position = platform.advance(delta.seconds());
+            let motion = position - tr.isometry.translation.vector;
+            tr.isometry.translation.vector = position;
+
+            moves.push(PlatformMove {
+                entity,
+                rect,
+                motion,
+                layer: col.layer,
+                one_way: col.one_way,
+            });
+        }
+
+        if moves.is_empty() {
+            return;
+        }
+
+        // The platforms are static colliders, already at their new position
+        let static_colliders = StaticCollider::collect(&transform, &collider, &moving);
+
+        for (entity, tr, mov, col) in (&*entities, &mut transform, &mut moving, &collider).join() {
+            let hits_tiles = interactions.collides(col.layer, CollisionLayer::Terrain);
+            let down = gravity.down(gravity_affected.get(entity));
+
+            for platform in &moves {
+                if !interactions.collides(col.layer, platform.layer) {
+                    continue;
+                }
+
+                // Carry the entities standing on the platform, unless they drop through it
+                let riding = rides(&tr.aabb(), &platform.rect, down);
+                if riding && !(platform.one_way && mov.drop_through) {
+                    // Stopped by what's in the way, like when moving on its own
+                    let mut carry = platform.motion;
+                    if hits_tiles {
+                        carry = tiles::sweep(&map, &tr.aabb(), carry, mov.drop_through).motion;
+                    }
+                    let carry = statics::sweep(
+                        &static_colliders,
+                        interactions,
+                        col.layer,
+                        &tr.aabb(),
+                        carry,
+                        down,
+                        mov.drop_through,
+                    )
+                    .motion;
+                    tr.isometry
+                        .append_translation_mut(&Translation2::from_vector(carry));
+                }
+
+                // Nothing is pushed by one-way platforms: it goes through them
+                if platform.one_way {
+                    continue;
+                }
+
+                let mut moved = platform.rect;
+                moved.x += platform.motion.x;
+                moved.y += platform.motion.y;
+
+                let push = match penetration(&tr.aabb(), &moved) {
+                    Some(push) => push,
+                    None => continue,
+                };
+                tr.isometry
+                    .append_translation_mut(&Translation2::from_vector(push));
+
+                // Stop the entity along the pushed axis, if it was moving against the push
+                let velocity = &mut mov.velocity.vector;
+                if push.x * velocity.x < 0. {
+                    velocity.x = 0.;
+                }
+                if push.y * velocity.y < 0. {
+                    velocity.y = 0.;
+                }
+
+                let rect = tr.aabb();
+                let crushed = (hits_tiles && tiles::overlaps(&map, &rect))
+                    || is_squeezed(&static_colliders, interactions, col.layer, &rect);
+                if crushed {
+                    events.push(CrushEvent {
+                        entity,
+                        platform: platform.entity,
+                    });
+                }
+            }
+        }
+    }
+}
+
+// Whether the rectangle stands on top of the platform, pulled towards `down` (normalized)
+fn rides(rect: &Rect, platform: &Rect, down: Vector2<f32>) -> bool {
+    let bottom = projection(rect, down).1;
+    let top = projection(platform, down).0;
+
+    // Across the gravity, they must overlap
+    let across = Vector2::new(down.y, -down.x);
+    let (start, end) = projection(rect, across);
+    let (platform_start, platform_end) = projection(platform, across);
+
+    (bottom - top).abs() <= RIDE_TOLERANCE && start < platform_end && platform_start < end
+}
+
+// Whether the rectangle is inside a solid static collider
+fn is_squeezed(
+    static_colliders: &[StaticCollider],
+    interactions: &InteractionMatrix,
+    layer: CollisionLayer,
+    rect: &Rect,
+) -> bool {
+    static_colliders
+        .iter()
+        .filter(|other| !other.one_way && interactions.collides(layer, other.layer))
+        .filter_map(|other| penetration(rect, &other.rect))
+        .any(|push| push.norm() > CRUSH_TOLERANCE)
+}
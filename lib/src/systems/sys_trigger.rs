@@ -0,0 +1,76 @@
+use collision::collision_handling::CollisionHandler;
+use components::trigger::Trigger;
+use ncollide::query::Proximity;
+use resources::trigger_events::{TriggerEvent, TriggerEvents};
+use specs::{Entities, Entity, Fetch, FetchMut, Join, System, WriteStorage};
+use std::collections::HashSet;
+
+// A system tracking the entities overlapping the triggers, based on the proximity events
+// of the last collision world update, and publishing the matching trigger events
+pub struct SysTrigger {}
+
+impl<'a> System<'a> for SysTrigger {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Trigger>,
+        Fetch<'a, CollisionHandler>,
+        FetchMut<'a, TriggerEvents>,
+    );
+
+    fn run(&mut self, (entities, mut triggers, collision_handler, mut events): Self::SystemData) {
+        events.clear();
+
+        // The (trigger, entity) pairs that started overlapping during this update
+        let mut entered: HashSet<(Entity, Entity)> = HashSet::new();
+
+        let world = &collision_handler.world;
+        for event in world.proximity_events() {
+            let (a, b) = match (
+                world.collision_object(event.collider1),
+                world.collision_object(event.collider2),
+            ) {
+                (Some(a), Some(b)) => (*a.data(), *b.data()),
+                _ => continue,
+            };
+
+            // Find out which one is the trigger
+            let (trigger_entity, entity) = if triggers.get(a).is_some() {
+                (a, b)
+            } else if triggers.get(b).is_some() {
+                (b, a)
+            } else {
+                continue;
+            };
+            let trigger = triggers.get_mut(trigger_entity).unwrap();
+
+            if event.new_status == Proximity::Intersecting {
+                if trigger.enter(entity) {
+                    entered.insert((trigger_entity, entity));
+                    events.push(TriggerEvent::Enter(trigger_entity, entity));
+                }
+            } else if event.prev_status == Proximity::Intersecting && trigger.exit(entity) {
+                events.push(TriggerEvent::Exit(trigger_entity, entity));
+            }
+        }
+
+        for (trigger_entity, trigger) in (&*entities, &mut triggers).join() {
+            // The despawned entities don't report their exit through ncollide
+            let dead: Vec<Entity> = trigger
+                .overlapping()
+                .iter()
+                .cloned()
+                .filter(|&entity| !entities.is_alive(entity))
+                .collect();
+            for entity in dead {
+                trigger.exit(entity);
+                events.push(TriggerEvent::Exit(trigger_entity, entity));
+            }
+
+            for &entity in trigger.overlapping() {
+                if !entered.contains(&(trigger_entity, entity)) {
+                    events.push(TriggerEvent::Stay(trigger_entity, entity));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use components::controller::CharacterController;
+use components::moving::{GravityAffected, Moving};
+use components::transform::Transform;
+use components::trigger::Trigger;
+use components::water::{submerged, Water};
+use nalgebra::Vector2;
+use resources::gravity::Gravity;
+use resources::trigger_events::{TriggerEvent, TriggerEvents};
+use resources::water_events::{Splash, WaterEvent, WaterEvents};
+use specs::{Entities, Fetch, FetchMut, Join, ReadStorage, System, WriteStorage};
+
+// A system applying the water to the moving entities overlapping it: the buoyancy pushing them
+// against the gravity pulling them (see `Gravity::applied`), and the drag slowing them down,
+// both in proportion to how deep they are; then publishing the water events of the ones which
+// entered or exited it
+//
+// The forces are only added to the entities: they are applied when they move.
+pub struct SysWater {}
+
+impl<'a> System<'a> for SysWater {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, Moving>,
+        ReadStorage<'a, GravityAffected>,
+        ReadStorage<'a, CharacterController>,
+        ReadStorage<'a, Trigger>,
+        ReadStorage<'a, Water>,
+        Fetch<'a, Gravity>,
+        Fetch<'a, TriggerEvents>,
+        FetchMut<'a, WaterEvents>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            transform,
+            mut moving,
+            gravity_affected,
+            controllers,
+            triggers,
+            waters,
+            gravity,
+            trigger_events,
+            mut events,
+        ) = data;
+        events.clear();
+
+        for (zone, trigger, water) in (&*entities, &triggers, &waters).join() {
+            let volume = match transform.get(zone) {
+                Some(tr) => tr.aabb(),
+                None => continue,
+            };
+
+            for &entity in trigger.overlapping() {
+                let (tr, mov) = match (transform.get(entity), moving.get_mut(entity)) {
+                    (Some(tr), Some(mov)) => (tr, mov),
+                    _ => continue,
+                };
+                let depth = submerged(&tr.aabb(), &volume);
+
+                // Against the gravity actually pulling it (the controller's own, for the
+                // characters), which may be scaled or turned
+                let pulling =
+                    gravity.applied(gravity_affected.get(entity), controllers.get(entity));
+                mov.add_force(-pulling * (water.density * depth));
+                let drag = -mov.velocity.vector * (water.drag * depth);
+                mov.add_force(drag);
+            }
+        }
+
+        for event in trigger_events.iter() {
+            let (zone, entity, entered) = match *event {
+                TriggerEvent::Enter(zone, entity) => (zone, entity, true),
+                TriggerEvent::Exit(zone, entity) => (zone, entity, false),
+                TriggerEvent::Stay(..) => continue,
+            };
+            if waters.get(zone).is_none() || !entities.is_alive(entity) {
+                continue;
+            }
+
+            // Crossing the surface, at the top of the water
+            let surface = match transform.get(zone) {
+                Some(tr) => tr.aabb().y,
+                None => continue,
+            };
+            let splash = match (transform.get(entity), moving.get(entity)) {
+                (Some(tr), Some(mov)) => Splash {
+                    water: zone,
+                    entity,
+                    position: Vector2::new(tr.isometry.translation.vector.x, surface),
+                    velocity: mov.velocity.vector,
+                },
+                _ => continue,
+            };
+            events.push(if entered {
+                WaterEvent::Enter(splash)
+            } else {
+                WaterEvent::Exit(splash)
+            });
+        }
+    }
+}
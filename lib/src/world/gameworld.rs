@@ -1,13 +1,45 @@
 use collision::collision_handling::CollisionHandler;
+use components::climbable::Climbable;
 use components::collider::Collider;
+use components::controller::CharacterController;
+use components::effector::AreaEffector;
+use components::gravity_zone::GravityZone;
+use components::grounded::Grounded;
+use components::input::Input;
+use components::jump::Jump;
 use components::moving::{GravityAffected, Moving};
+use components::platform::{MovingPlatform, PlatformState};
 use components::rect_drawable::RectDrawable;
+use components::rigid_body::RigidBody;
 use components::transform::Transform;
+use components::trigger::Trigger;
+use components::water::Water;
 use entities::game_entity::GameEntity;
+use entities::platform::Platform;
+use entities::trigger::TriggerZone;
+use map::Map;
+use nalgebra::Point2;
+use resources::ccd::CcdSettings;
+use resources::collision_events::CollisionEvents;
+use resources::crush_events::CrushEvents;
 use resources::delta_time::DeltaTime;
-use specs::{Dispatcher, DispatcherBuilder, World};
+use resources::gravity::Gravity;
+use resources::trigger_events::TriggerEvents;
+use resources::water_events::WaterEvents;
+use specs::{Dispatcher, DispatcherBuilder, Entity, Join, World};
+use std::time::Duration;
+use systems::sys_bodies::SysBodies;
 use systems::sys_colliding::SysCollide;
+use systems::sys_controller::SysController;
+use systems::sys_effectors::SysEffectors;
+use systems::sys_gravity_zones::SysGravityZones;
+use systems::sys_grounded::SysGrounded;
+use systems::sys_jump::SysJump;
 use systems::sys_moving::{SysMoving, SysMovingGravity};
+use systems::sys_moving_collision::SysMovingCollision;
+use systems::sys_platform::SysPlatforms;
+use systems::sys_trigger::SysTrigger;
+use systems::sys_water::SysWater;
 
 // The basic struct of the game. Contains everything to simulate an instance of the game.
 pub struct GameWorld<'a, 'b> {
@@ -27,23 +59,60 @@ impl<'a, 'b> GameWorld<'a, 'b> {
         world.register::<Moving>();
         world.register::<GravityAffected>();
         world.register::<Collider>();
+        world.register::<Trigger>();
+        world.register::<GravityZone>();
+        world.register::<AreaEffector>();
+        world.register::<Climbable>();
+        world.register::<Water>();
+        world.register::<Grounded>();
+        world.register::<Jump>();
+        world.register::<Input>();
+        world.register::<CharacterController>();
+        world.register::<MovingPlatform>();
+        world.register::<RigidBody>();
 
         let collision_handler: CollisionHandler = CollisionHandler::new();
 
         world.add_resource(DeltaTime::new());
         world.add_resource(collision_handler);
+        world.add_resource(Map::default());
+        world.add_resource(Gravity::new());
+        world.add_resource(CcdSettings::new());
+        world.add_resource(CollisionEvents::new());
+        world.add_resource(TriggerEvents::new());
+        world.add_resource(CrushEvents::new());
+        world.add_resource(WaterEvents::new());
 
         // Creates the systems
-        let sys_moving_gravity = SysMovingGravity::new();
+        let sys_platforms = SysPlatforms {};
+        let sys_effectors = SysEffectors {};
+        let sys_water = SysWater {};
+        let sys_gravity_zones = SysGravityZones {};
+        let sys_controller = SysController {};
+        let sys_jump = SysJump {};
+        let sys_moving_gravity = SysMovingGravity {};
         let sys_moving = SysMoving {};
+        let sys_moving_collision = SysMovingCollision {};
         let sys_moving_collide = SysCollide {};
+        let sys_bodies = SysBodies {};
+        let sys_grounded = SysGrounded {};
+        let sys_trigger = SysTrigger {};
 
         // Creates the dispatcher, registering the systems
         let logic_dispatcher: Dispatcher = DispatcherBuilder::new()
-			.add(sys_moving_gravity, "sys_moving_gravity", &[])
-			// TODO: Add sys_moving_collision
+			.add(sys_platforms, "sys_platforms", &[])
+			.add(sys_effectors, "sys_effectors", &["sys_platforms"])
+			.add(sys_water, "sys_water", &["sys_effectors"])
+			.add(sys_gravity_zones, "sys_gravity_zones", &[])
+			.add(sys_controller, "sys_controller", &["sys_water", "sys_gravity_zones"])
+			.add(sys_jump, "sys_jump", &["sys_controller"])
+			.add(sys_moving_gravity, "sys_moving_gravity", &["sys_jump"])
 			.add(sys_moving, "sys_moving", &["sys_moving_gravity"])
-			.add(sys_moving_collide, "sys_moving_colliding", &["sys_moving"])
+			.add(sys_moving_collision, "sys_moving_collision", &["sys_moving"])
+			.add(sys_moving_collide, "sys_moving_colliding", &["sys_moving_collision"])
+			.add(sys_bodies, "sys_bodies", &["sys_moving_colliding"])
+			.add(sys_grounded, "sys_grounded", &["sys_bodies"])
+			.add(sys_trigger, "sys_trigger", &["sys_grounded"])
 			.build();
 
         // Creates the actual GameWorld
@@ -53,6 +122,7 @@ impl<'a, 'b> GameWorld<'a, 'b> {
         }
     }
 
+    // Updates the game, by the time elapsed since the last update
     pub fn update(&mut self) {
         {
             let mut delta_time = self.entity_world.write_resource::<DeltaTime>();
@@ -63,8 +133,74 @@ impl<'a, 'b> GameWorld<'a, 'b> {
         self.logic_dispatcher.dispatch(&self.entity_world.res);
     }
 
-    pub fn add_game_entity<T: GameEntity>(&mut self, entity: T) {
-        entity.add_to_world(&mut self.entity_world);
+    // Updates the game by a fixed time step
+    pub fn update_by(&mut self, delta: Duration) {
+        self.entity_world.write_resource::<DeltaTime>().set(delta);
+
+        self.logic_dispatcher.dispatch(&self.entity_world.res);
+    }
+
+    // Replaces the map, shared by the systems as a resource, and its moving platforms,
+    // triggers, area effectors and water
+    //
+    // All the triggers (the area effectors and the water among them) are parts of the level, so
+    // they go with the old map.
+    pub fn set_map(&mut self, map: Map) {
+        let old_entities: Vec<Entity> = {
+            let entities = self.entity_world.entities();
+            let platforms = self.entity_world.read::<MovingPlatform>();
+            let triggers = self.entity_world.read::<Trigger>();
+            let old_platforms = (&*entities, &platforms).join().map(|(e, _)| e);
+            let old_triggers = (&*entities, &triggers).join().map(|(e, _)| e);
+            old_platforms.chain(old_triggers).collect()
+        };
+        self.entity_world
+            .delete_entities(&old_entities)
+            .expect("the map's entities are alive");
+
+        for (index, data) in map.moving_platforms.iter().enumerate() {
+            self.add_game_entity(Platform::new(index, data.clone()));
+        }
+        for data in &map.triggers {
+            let position = Point2::new(data.position.0, data.position.1);
+            let size = Point2::new(data.size.0, data.size.1);
+            self.add_game_entity(TriggerZone::new(position, size, data.kind.clone()));
+        }
+        for data in &map.effectors {
+            let position = Point2::new(data.position.0, data.position.1);
+            let size = Point2::new(data.size.0, data.size.1);
+            self.add_game_entity(TriggerZone::effector(position, size, data.effector));
+        }
+        for data in &map.water {
+            let position = Point2::new(data.position.0, data.position.1);
+            let size = Point2::new(data.size.0, data.size.1);
+            self.add_game_entity(TriggerZone::water(position, size, data.water));
+        }
+
+        *self.entity_world.write_resource::<Map>() = map;
+    }
+
+    // Where the moving platforms are along their path, to replicate them
+    pub fn platform_states(&self) -> Vec<PlatformState> {
+        let platforms = self.entity_world.read::<MovingPlatform>();
+        let mut states: Vec<PlatformState> = (&platforms).join().map(|p| p.state()).collect();
+        states.sort_by_key(|state| state.index);
+
+        states
+    }
+
+    // Puts the moving platforms where the states tell (e.g. as received from the server)
+    pub fn set_platform_states(&mut self, states: &[PlatformState]) {
+        let mut platforms = self.entity_world.write::<MovingPlatform>();
+        for platform in (&mut platforms).join() {
+            if let Some(state) = states.iter().find(|state| state.index == platform.index) {
+                platform.set_state(state);
+            }
+        }
+    }
+
+    pub fn add_game_entity<T: GameEntity>(&mut self, entity: T) -> T::Entity {
+        entity.add_to_world(&mut self.entity_world)
     }
 }
 
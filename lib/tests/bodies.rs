@@ -0,0 +1,210 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{add_body, add_player, approx_eq, color, position, set_velocity, velocity, X};
+use lib::components::grounded::Grounded;
+use lib::components::moving::GravityAffected;
+use lib::components::rigid_body::RigidBody;
+use lib::entities::ground::Ground;
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::RunNow;
+use lib::systems::sys_bodies::SysBodies;
+use lib::systems::sys_colliding::SysCollide;
+use lib::systems::sys_grounded::SysGrounded;
+use lib::systems::sys_moving::SysMoving;
+use lib::world::gameworld::GameWorld;
+use std::time::Duration;
+
+// A ground whose top is at y = 90
+fn add_ground(world: &mut GameWorld) {
+    world.add_game_entity(Ground::new(
+        Point2::new(X, 100.),
+        Point2::new(400., 20.),
+        color(),
+    ));
+}
+
+// Finds the contacts, then solves them
+fn solve(world: &mut GameWorld) {
+    SysCollide {}.run_now(&world.entity_world.res);
+    SysBodies {}.run_now(&world.entity_world.res);
+}
+
+#[test]
+fn players_push_bodies() {
+    let mut world = GameWorld::new();
+    // Overlapping the crate by 2px
+    let player = add_player(&mut world, 0., 0.);
+    let body = add_body(&mut world, 30., 0., RigidBody::new(1.));
+    set_velocity(&mut world, player, Vector2::new(300., 0.));
+
+    solve(&mut world);
+
+    // Of the same mass, they share the push and end up moving together
+    assert!(approx_eq(position(&world, player), Vector2::new(-1., 0.)));
+    assert!(approx_eq(position(&world, body), Vector2::new(31., 0.)));
+    assert!(approx_eq(velocity(&world, player), Vector2::new(150., 0.)));
+    assert!(approx_eq(velocity(&world, body), Vector2::new(150., 0.)));
+}
+
+#[test]
+fn heavy_bodies_are_harder_to_push() {
+    let mut world = GameWorld::new();
+    let player = add_player(&mut world, 0., 0.);
+    let boulder = add_body(&mut world, 30., 0., RigidBody::new(3.));
+    set_velocity(&mut world, player, Vector2::new(300., 0.));
+
+    solve(&mut world);
+
+    assert!(approx_eq(position(&world, player), Vector2::new(-1.5, 0.)));
+    assert!(approx_eq(position(&world, boulder), Vector2::new(30.5, 0.)));
+    assert!(approx_eq(velocity(&world, player), Vector2::new(75., 0.)));
+    assert!(approx_eq(velocity(&world, boulder), Vector2::new(75., 0.)));
+}
+
+#[test]
+fn bodies_can_be_stacked() {
+    let mut world = GameWorld::new();
+    add_ground(&mut world);
+    // Resting on the ground
+    let bottom = add_body(&mut world, 0., 74., RigidBody::new(1.));
+    // Fell 2px into the bottom crate
+    let top = add_body(&mut world, 0., 44., RigidBody::new(1.));
+    set_velocity(&mut world, top, Vector2::new(0., 100.));
+
+    solve(&mut world);
+
+    // The bottom crate is held by the ground: the top one is pushed back and stops
+    assert!(approx_eq(position(&world, bottom), Vector2::new(0., 74.)));
+    assert!(approx_eq(position(&world, top), Vector2::new(0., 42.)));
+    assert!(approx_eq(velocity(&world, bottom), Vector2::new(0., 0.)));
+    assert!(approx_eq(velocity(&world, top), Vector2::new(0., 0.)));
+}
+
+#[test]
+fn bodies_carry_what_stands_on_them_by_friction() {
+    let mut world = GameWorld::new();
+    add_ground(&mut world);
+    let body = add_body(&mut world, 0., 74., RigidBody::new(1.));
+    // Fell 1px into the crate
+    let player = add_player(&mut world, 0., 43.);
+    set_velocity(&mut world, body, Vector2::new(100., 0.));
+    set_velocity(&mut world, player, Vector2::new(0., 50.));
+
+    solve(&mut world);
+
+    // The friction can't exceed the friction coefficient (0.5) times the normal impulse
+    assert!(approx_eq(velocity(&world, player), Vector2::new(25., 0.)));
+    assert!(approx_eq(velocity(&world, body), Vector2::new(100., 0.)));
+}
+
+#[test]
+fn bodies_bounce_back_unless_resting() {
+    let mut world = GameWorld::new();
+    add_ground(&mut world);
+    let ball = add_body(
+        &mut world,
+        0.,
+        58.,
+        RigidBody::new(1.).with_restitution(0.5),
+    );
+    let body = add_body(
+        &mut world,
+        100.,
+        72.,
+        RigidBody::new(1.).with_restitution(0.5),
+    );
+    set_velocity(&mut world, ball, Vector2::new(0., 200.));
+    set_velocity(&mut world, body, Vector2::new(0., 40.));
+
+    world
+        .entity_world
+        .write_resource::<DeltaTime>()
+        .set(Duration::from_millis(100));
+    SysMoving {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(position(&world, ball), Vector2::new(0., 74.)));
+    assert!(approx_eq(velocity(&world, ball), Vector2::new(0., -100.)));
+
+    // Too slow to bounce, it rests on the ground
+    assert!(approx_eq(position(&world, body), Vector2::new(100., 74.)));
+    assert!(approx_eq(velocity(&world, body), Vector2::new(0., 0.)));
+}
+
+#[test]
+fn bodies_rub_against_the_ground_as_hard_as_their_gravity() {
+    let mut world = GameWorld::new();
+    add_ground(&mut world);
+    let heavy = add_body(&mut world, 0., 74., RigidBody::new(1.));
+    let light = add_body(&mut world, 100., 74., RigidBody::new(1.));
+    world
+        .entity_world
+        .write::<GravityAffected>()
+        .get_mut(light)
+        .unwrap()
+        .scale = 0.5;
+    set_velocity(&mut world, heavy, Vector2::new(-300., 0.));
+    set_velocity(&mut world, light, Vector2::new(300., 0.));
+
+    world
+        .entity_world
+        .write_resource::<DeltaTime>()
+        .set(Duration::from_millis(100));
+    SysGrounded {}.run_now(&world.entity_world.res);
+    SysMoving {}.run_now(&world.entity_world.res);
+
+    // Slowed down by their friction coefficient (0.5) times their gravity
+    assert!(approx_eq(velocity(&world, heavy), Vector2::new(-225., 0.)));
+    assert!(approx_eq(velocity(&world, light), Vector2::new(262.5, 0.)));
+}
+
+#[test]
+fn bodies_can_be_stood_on() {
+    let mut world = GameWorld::new();
+    let body = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    let player = add_player(&mut world, 0., -32.);
+
+    SysGrounded {}.run_now(&world.entity_world.res);
+
+    let grounded = world.entity_world.read::<Grounded>();
+    assert!(grounded.get(player).unwrap().on_ground);
+    assert!(!grounded.get(body).unwrap().on_ground);
+}
+
+#[test]
+fn contacts_are_solved_in_the_same_order_on_every_peer() {
+    // The same scene, with entities created in different orders
+    let mut first = GameWorld::new();
+    add_ground(&mut first);
+    let first_player = add_player(&mut first, -30., 74.);
+    let first_bodies = [
+        add_body(&mut first, 0., 74., RigidBody::new(1.)),
+        add_body(&mut first, 30., 74., RigidBody::new(2.)),
+        add_body(&mut first, 15., 45., RigidBody::new(1.)),
+    ];
+
+    let mut second = GameWorld::new();
+    add_ground(&mut second);
+    let second_bodies = [
+        add_body(&mut second, 15., 45., RigidBody::new(1.)),
+        add_body(&mut second, 30., 74., RigidBody::new(2.)),
+        add_body(&mut second, 0., 74., RigidBody::new(1.)),
+    ];
+    let second_player = add_player(&mut second, -30., 74.);
+
+    set_velocity(&mut first, first_player, Vector2::new(300., 0.));
+    set_velocity(&mut second, second_player, Vector2::new(300., 0.));
+    solve(&mut first);
+    solve(&mut second);
+
+    assert_eq!(
+        position(&first, first_player),
+        position(&second, second_player)
+    );
+    for (&a, &b) in first_bodies.iter().zip(second_bodies.iter().rev()) {
+        assert_eq!(position(&first, a), position(&second, b));
+        assert_eq!(velocity(&first, a), velocity(&second, b));
+    }
+}
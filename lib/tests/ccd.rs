@@ -0,0 +1,124 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{color, position, set_velocity, step, velocity, X};
+
+use lib::collision::resolution::time_of_impact;
+use lib::entities::ground::Ground;
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::ccd::CcdSettings;
+use lib::specs::Entity;
+use lib::types::Rect;
+use lib::world::gameworld::GameWorld;
+
+fn add_player(world: &mut GameWorld, y: f32, velocity: Vector2<f32>) -> Entity {
+    let player = common::add_player(world, 0., y);
+    set_velocity(world, player, velocity);
+
+    player
+}
+
+#[test]
+fn fast_fall_stops_on_a_thin_platform() {
+    let mut world = GameWorld::new();
+    // A 4px thick platform, its top is at y = 298
+    world.add_game_entity(Ground::new(
+        Point2::new(X, 300.),
+        Point2::new(200., 4.),
+        color(),
+    ));
+    // Falling by 1000px in a single update
+    let player = add_player(&mut world, 0., Vector2::new(0., 1000.));
+
+    step(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(0., 282.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn fast_fall_stops_on_a_thin_one_way_platform() {
+    let mut world = GameWorld::new();
+    world.add_game_entity(
+        Ground::new(Point2::new(X, 300.), Point2::new(200., 4.), color()).one_way(),
+    );
+    let player = add_player(&mut world, 0., Vector2::new(0., 1000.));
+
+    step(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(0., 282.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn fast_jump_goes_through_a_one_way_platform() {
+    let mut world = GameWorld::new();
+    world.add_game_entity(
+        Ground::new(Point2::new(X, 300.), Point2::new(200., 4.), color()).one_way(),
+    );
+    let player = add_player(&mut world, 600., Vector2::new(0., -500.));
+
+    step(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(0., 100.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., -500.));
+}
+
+#[test]
+fn fast_diagonal_move_slides_along_the_ground() {
+    let mut world = GameWorld::new();
+    world.add_game_entity(Ground::new(
+        Point2::new(X, 300.),
+        Point2::new(2000., 4.),
+        color(),
+    ));
+    let player = add_player(&mut world, 200., Vector2::new(300., 300.));
+
+    step(&mut world);
+
+    // Stops falling on the platform, and keeps going right
+    let position = position(&world, player);
+    assert!((position.x - 300.).abs() < 0.01);
+    assert!((position.y - 282.).abs() < 0.01);
+    assert_eq!(velocity(&world, player), Vector2::new(300., 0.));
+}
+
+#[test]
+fn fast_moves_are_sub_stepped() {
+    let settings = CcdSettings {
+        substep_threshold: 10.,
+        max_substeps: 8,
+    };
+
+    assert_eq!(settings.substeps(Vector2::new(0., 5.)), 1);
+    assert_eq!(settings.substeps(Vector2::new(0., 10.)), 1);
+    assert_eq!(settings.substeps(Vector2::new(30., 40.)), 5);
+    assert_eq!(settings.substeps(Vector2::new(0., 1000.)), 8);
+}
+
+#[test]
+fn time_of_impact_on_each_side() {
+    let wall = Rect::new(100., 0., 20., 100.);
+
+    // From the left
+    let rect = Rect::new(60., 10., 20., 20.);
+    assert_eq!(
+        time_of_impact(&rect, Vector2::new(40., 0.), &wall),
+        Some((0.5, Vector2::new(-1., 0.)))
+    );
+    // From above
+    let rect = Rect::new(100., -40., 20., 20.);
+    assert_eq!(
+        time_of_impact(&rect, Vector2::new(0., 80.), &wall),
+        Some((0.25, Vector2::new(0., -1.)))
+    );
+    // Too short
+    let rect = Rect::new(60., 10., 20., 20.);
+    assert_eq!(time_of_impact(&rect, Vector2::new(10., 0.), &wall), None);
+    // Going away
+    assert_eq!(time_of_impact(&rect, Vector2::new(-40., 0.), &wall), None);
+    // Sliding along the top
+    let rect = Rect::new(80., -20., 20., 20.);
+    assert_eq!(time_of_impact(&rect, Vector2::new(40., 0.), &wall), None);
+}
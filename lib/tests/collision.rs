@@ -0,0 +1,559 @@
+extern crate some_platformer_lib as lib;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::{CollisionLayer, Interaction, InteractionMatrix};
+use lib::components::collider::Collider;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::transform::Transform;
+use lib::components::trigger::{Trigger, TriggerKind};
+use lib::entities::game_entity::GameEntity;
+use lib::entities::ground::Ground;
+use lib::entities::player::Player;
+use lib::entities::trigger::TriggerZone;
+use lib::map::{Map, TriggerData};
+use lib::nalgebra::{Point2, Translation2, Vector2};
+use lib::resources::collision_events::{CollisionEvent, CollisionEvents};
+use lib::resources::gravity::Gravity;
+use lib::resources::trigger_events::{TriggerEvent, TriggerEvents};
+use lib::specs::{Entity, Join, RunNow, World};
+use lib::systems::sys_colliding::SysCollide;
+use lib::systems::sys_moving_collision::SysMovingCollision;
+use lib::systems::sys_trigger::SysTrigger;
+use lib::types::Color;
+use lib::world::gameworld::GameWorld;
+use std::collections::HashMap;
+
+fn new_world() -> World {
+    let mut world = World::new();
+    world.register::<Transform>();
+    world.register::<Moving>();
+    world.register::<GravityAffected>();
+    world.register::<Collider>();
+    world.register::<Trigger>();
+    world.add_resource(CollisionHandler::new());
+    world.add_resource(Gravity::new());
+    world.add_resource(CollisionEvents::new());
+    world.add_resource(TriggerEvents::new());
+    world
+}
+
+fn add_collider(world: &mut World, entity: Entity, layer: CollisionLayer) {
+    let collider = {
+        let transforms = world.read::<Transform>();
+        world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            transforms.get(entity).unwrap(),
+            layer,
+        )
+    };
+    world.write::<Collider>().insert(entity, collider);
+}
+
+fn add_static(world: &mut World, x: f32, y: f32, w: f32, h: f32) -> Entity {
+    add_static_on(world, CollisionLayer::Terrain, x, y, w, h)
+}
+
+fn add_static_on(
+    world: &mut World,
+    layer: CollisionLayer,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+) -> Entity {
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(w, h)))
+        .build();
+    add_collider(world, entity, layer);
+
+    entity
+}
+
+fn add_moving(world: &mut World, x: f32, y: f32, vx: f32, vy: f32) -> Entity {
+    let mut moving = Moving::new();
+    moving.velocity = Translation2::new(vx, vy);
+
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(32., 32.)))
+        .with(moving)
+        .build();
+    add_collider(world, entity, CollisionLayer::Player);
+
+    entity
+}
+
+fn resolve(world: &mut World) {
+    SysMovingCollision {}.run_now(&world.res);
+    world.maintain();
+}
+
+fn position(world: &World, entity: Entity) -> Vector2<f32> {
+    world
+        .read::<Transform>()
+        .get(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+}
+
+fn velocity(world: &World, entity: Entity) -> Vector2<f32> {
+    world.read::<Moving>().get(entity).unwrap().velocity.vector
+}
+
+#[test]
+fn lands_on_the_ground() {
+    let mut world = new_world();
+    // The ground top is at y = 90
+    add_static(&mut world, 100., 100., 200., 20.);
+    // The player bottom is at y = 95, falling
+    let player = add_moving(&mut world, 100., 79., 1., 5.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 74.));
+    assert_eq!(velocity(&world, player), Vector2::new(1., 0.));
+}
+
+#[test]
+fn stops_against_a_wall() {
+    let mut world = new_world();
+    // The wall left side is at x = 90
+    add_static(&mut world, 100., 0., 20., 200.);
+    // The player right side is at x = 94, moving right
+    let player = add_moving(&mut world, 78., 0., 4., 2.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(74., 0.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 2.));
+}
+
+#[test]
+fn does_not_snag_on_seams() {
+    let mut world = new_world();
+    // Two blocks side by side, the seam is at x = 32
+    add_static(&mut world, 16., 100., 32., 32.);
+    add_static(&mut world, 48., 100., 32., 32.);
+    // The player barely overlaps the left block, and mostly stands on the right one
+    let player = add_moving(&mut world, 46., 71., 0., 3.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(46., 68.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn stops_in_a_corner() {
+    let mut world = new_world();
+    // A floor (top at y = 84) and a wall (left side at x = 100)
+    add_static(&mut world, 100., 100., 200., 32.);
+    add_static(&mut world, 110., 8., 20., 216.);
+    // The player is moving diagonally into the corner
+    let player = add_moving(&mut world, 86., 71., 2., 3.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(84., 68.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn moving_away_keeps_its_velocity() {
+    let mut world = new_world();
+    add_static(&mut world, 100., 100., 200., 20.);
+    // Overlapping the ground, but already jumping
+    let player = add_moving(&mut world, 100., 76., 0., -5.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 74.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., -5.));
+}
+
+#[test]
+fn entities_register_their_collider() {
+    let mut game_world = GameWorld::new();
+    let color = Color::new(1., 1., 1., 1.);
+    let ground = game_world.add_game_entity(Ground::new(
+        Point2::new(100., 100.),
+        Point2::new(200., 20.),
+        color,
+    ));
+    let player = game_world.add_game_entity(Player::default());
+
+    let world = &game_world.entity_world;
+    let handler = world.read_resource::<CollisionHandler>();
+    let colliders = world.read::<Collider>();
+    for entity in &[ground.entity(), player.entity()] {
+        let handle = colliders.get(*entity).unwrap().collision_object_handle;
+        let object = handler.world.collision_object(handle).unwrap();
+        assert_eq!(object.data(), entity);
+    }
+}
+
+#[test]
+fn despawning_removes_the_collider() {
+    let mut world = new_world();
+    let ground = add_static(&mut world, 100., 100., 200., 20.);
+    let player = add_moving(&mut world, 100., 0., 0., 0.);
+    let handle = world
+        .read::<Collider>()
+        .get(player)
+        .unwrap()
+        .collision_object_handle;
+
+    world.delete_entity(player).unwrap();
+    world.maintain();
+    SysCollide {}.run_now(&world.res);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    assert!(handler.world.collision_object(handle).is_none());
+    assert_eq!(handler.world.collision_objects().count(), 1);
+    assert_eq!(
+        handler.world.collision_objects().next().unwrap().data(),
+        &ground
+    );
+}
+
+#[test]
+fn only_blocking_layers_are_resolved() {
+    let mut world = new_world();
+    // A trigger zone is not solid, the player goes through it
+    add_static_on(&mut world, CollisionLayer::Trigger, 100., 100., 200., 20.);
+    let player = add_moving(&mut world, 100., 79., 0., 5.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 79.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 5.));
+}
+
+#[test]
+fn interaction_matrix_is_symmetric() {
+    let matrix = InteractionMatrix::new().with(
+        CollisionLayer::Enemy,
+        CollisionLayer::Pickup,
+        Interaction::Collide,
+    );
+
+    assert!(matrix.collides(CollisionLayer::Pickup, CollisionLayer::Enemy));
+    assert!(matrix.generates_events(CollisionLayer::Enemy, CollisionLayer::Pickup));
+    assert_eq!(
+        matrix.get(CollisionLayer::Enemy, CollisionLayer::Player),
+        Interaction::None
+    );
+    assert!(!matrix.is_solid(CollisionLayer::Player));
+
+    let default = InteractionMatrix::default();
+    assert!(default.collides(CollisionLayer::Terrain, CollisionLayer::Player));
+    assert!(!default.collides(CollisionLayer::Player, CollisionLayer::Pickup));
+    assert!(default.generates_events(CollisionLayer::Player, CollisionLayer::Pickup));
+    assert!(!default.is_solid(CollisionLayer::Trigger));
+}
+
+#[test]
+fn contact_events_are_published() {
+    let mut world = new_world();
+    let ground = add_static(&mut world, 100., 100., 200., 20.);
+    // Overlapping the ground
+    let player = add_moving(&mut world, 100., 79., 0., 0.);
+
+    SysCollide {}.run_now(&world.res);
+    {
+        let events: Vec<CollisionEvent> = world
+            .read_resource::<CollisionEvents>()
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert!(match events[0] {
+            CollisionEvent::Started(_, _) => true,
+            _ => false,
+        });
+        assert_eq!(events[0].other(player), Some(ground));
+    }
+
+    // Nothing changed, nothing is published
+    SysCollide {}.run_now(&world.res);
+    assert!(world.read_resource::<CollisionEvents>().is_empty());
+
+    // Jump far away from the ground
+    world
+        .write::<Transform>()
+        .get_mut(player)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+        .y = -100.;
+    SysCollide {}.run_now(&world.res);
+    {
+        let events = world.read_resource::<CollisionEvents>();
+        assert_eq!(events.len(), 1);
+        let event = events.iter().next().unwrap();
+        assert!(match *event {
+            CollisionEvent::Stopped(_, _) => true,
+            _ => false,
+        });
+        assert_eq!(event.other(ground), Some(player));
+    }
+}
+
+#[test]
+fn despawned_entities_stop_touching() {
+    let mut world = new_world();
+    let ground = add_static(&mut world, 100., 100., 200., 20.);
+    // Overlapping the ground
+    let player = add_moving(&mut world, 100., 79., 0., 0.);
+    SysCollide {}.run_now(&world.res);
+
+    world.delete_entity(player).unwrap();
+    world.maintain();
+    SysCollide {}.run_now(&world.res);
+
+    let events = world.read_resource::<CollisionEvents>();
+    assert_eq!(events.len(), 1);
+    let event = events.iter().next().unwrap();
+    assert!(match *event {
+        CollisionEvent::Stopped(_, _) => true,
+        _ => false,
+    });
+    assert_eq!(event.other(ground), Some(player));
+}
+
+fn move_to(world: &mut World, entity: Entity, x: f32, y: f32) {
+    world
+        .write::<Transform>()
+        .get_mut(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector = Vector2::new(x, y);
+}
+
+fn trigger_events(world: &mut World) -> Vec<TriggerEvent> {
+    SysCollide {}.run_now(&world.res);
+    SysTrigger {}.run_now(&world.res);
+    world.maintain();
+
+    world
+        .read_resource::<TriggerEvents>()
+        .iter()
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn triggers_report_enter_stay_and_exit() {
+    let mut world = new_world();
+    let zone = TriggerZone::new(
+        Point2::new(100., 100.),
+        Point2::new(50., 50.),
+        TriggerKind::Checkpoint,
+    )
+    .add_to_world(&mut world)
+    .entity();
+    let player = add_moving(&mut world, 0., 0., 0., 0.);
+
+    assert_eq!(trigger_events(&mut world), vec![]);
+
+    move_to(&mut world, player, 100., 90.);
+    assert_eq!(
+        trigger_events(&mut world),
+        vec![TriggerEvent::Enter(zone, player)]
+    );
+    assert_eq!(
+        trigger_events(&mut world),
+        vec![TriggerEvent::Stay(zone, player)]
+    );
+    assert!(world
+        .read::<Trigger>()
+        .get(zone)
+        .unwrap()
+        .is_overlapped_by(player));
+
+    move_to(&mut world, player, 300., 90.);
+    assert_eq!(
+        trigger_events(&mut world),
+        vec![TriggerEvent::Exit(zone, player)]
+    );
+    assert_eq!(trigger_events(&mut world), vec![]);
+}
+
+#[test]
+fn despawned_entities_exit_triggers() {
+    let mut world = new_world();
+    let zone = TriggerZone::new(
+        Point2::new(100., 100.),
+        Point2::new(50., 50.),
+        TriggerKind::KillZone,
+    )
+    .add_to_world(&mut world)
+    .entity();
+    let player = add_moving(&mut world, 100., 100., 0., 0.);
+
+    assert_eq!(
+        trigger_events(&mut world),
+        vec![TriggerEvent::Enter(zone, player)]
+    );
+
+    world.delete_entity(player).unwrap();
+    assert_eq!(
+        trigger_events(&mut world),
+        vec![TriggerEvent::Exit(zone, player)]
+    );
+}
+
+#[test]
+fn map_triggers_are_replaced_with_the_map() {
+    let mut game_world = GameWorld::new();
+    let map = Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: vec![TriggerData {
+            position: (100., 100.),
+            size: (50., 50.),
+            kind: TriggerKind::Script("intro".into()),
+        }],
+        effectors: Vec::new(),
+        water: Vec::new(),
+    };
+    let kinds = |game_world: &GameWorld| -> Vec<TriggerKind> {
+        let triggers = game_world.entity_world.read::<Trigger>();
+        (&triggers).join().map(|t| t.kind.clone()).collect()
+    };
+
+    game_world.set_map(map.clone());
+    game_world.set_map(map);
+    assert_eq!(
+        kinds(&game_world),
+        vec![TriggerKind::Script("intro".into())]
+    );
+
+    game_world.set_map(Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    });
+    assert_eq!(kinds(&game_world), vec![]);
+}
+
+fn add_platform(world: &mut World, x: f32, y: f32, w: f32, h: f32) -> Entity {
+    let platform = add_static(world, x, y, w, h);
+    world.write::<Collider>().get_mut(platform).unwrap().one_way = true;
+
+    platform
+}
+
+fn moved_from(world: &mut World, entity: Entity, x: f32, y: f32) {
+    world
+        .write::<Moving>()
+        .get_mut(entity)
+        .unwrap()
+        .previous_position = Some(Vector2::new(x, y));
+}
+
+#[test]
+fn lands_on_one_way_platforms() {
+    let mut world = new_world();
+    // The platform top is at y = 90
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // The player bottom was at y = 88, it is now at y = 95
+    let player = add_moving(&mut world, 100., 79., 0., 7.);
+    moved_from(&mut world, player, 100., 72.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 74.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn jumps_through_one_way_platforms() {
+    let mut world = new_world();
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // Coming from below, already halfway through the platform
+    let player = add_moving(&mut world, 100., 100., 0., -10.);
+    moved_from(&mut world, player, 100., 110.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 100.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., -10.));
+}
+
+#[test]
+fn walks_through_one_way_platforms() {
+    let mut world = new_world();
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // Coming from the left side, below the platform top
+    let player = add_moving(&mut world, -10., 100., 5., 0.);
+    moved_from(&mut world, player, -15., 100.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(-10., 100.));
+    assert_eq!(velocity(&world, player), Vector2::new(5., 0.));
+}
+
+// Turns the gravity of the entity upside down
+fn flip(world: &mut World, entity: Entity) {
+    let affected = GravityAffected::new().with_direction(Vector2::new(0., -1.));
+    world.write::<GravityAffected>().insert(entity, affected);
+}
+
+#[test]
+fn lands_upside_down_under_one_way_platforms() {
+    let mut world = new_world();
+    // The platform bottom is at y = 110
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // Pulled up, the player top was at y = 112, it is now at y = 105
+    let player = add_moving(&mut world, 100., 121., 0., -7.);
+    flip(&mut world, player);
+    moved_from(&mut world, player, 100., 128.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 126.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 0.));
+}
+
+#[test]
+fn jumps_upside_down_through_one_way_platforms() {
+    let mut world = new_world();
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // Coming from above, against its gravity
+    let player = add_moving(&mut world, 100., 79., 0., 7.);
+    flip(&mut world, player);
+    moved_from(&mut world, player, 100., 72.);
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 79.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 7.));
+}
+
+#[test]
+fn drops_through_one_way_platforms() {
+    let mut world = new_world();
+    add_platform(&mut world, 100., 100., 200., 20.);
+    // Standing on the platform, and holding down
+    let player = add_moving(&mut world, 100., 79., 0., 5.);
+    moved_from(&mut world, player, 100., 74.);
+    world
+        .write::<Moving>()
+        .get_mut(player)
+        .unwrap()
+        .drop_through = true;
+
+    resolve(&mut world);
+
+    assert_eq!(position(&world, player), Vector2::new(100., 79.));
+    assert_eq!(velocity(&world, player), Vector2::new(0., 5.));
+}
@@ -0,0 +1,118 @@
+// The fixtures shared by the integration tests, built on a `GameWorld`: each test file only
+// uses some of them
+#![allow(dead_code)]
+
+use lib::components::moving::Moving;
+use lib::components::rigid_body::RigidBody;
+use lib::components::transform::Transform;
+use lib::components::trigger::Trigger;
+use lib::entities::body::Body;
+use lib::entities::player::Player;
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::resources::trigger_events::{TriggerEvent, TriggerEvents};
+use lib::specs::{Entity, RunNow};
+use lib::systems::sys_moving::SysMoving;
+use lib::types::Color;
+use lib::world::gameworld::GameWorld;
+use std::time::Duration;
+
+// Far away from the tiles of the debug map: the entities are placed relatively to it
+pub const X: f32 = 2000.;
+
+// The frame rate of the worlds created by `new_world`
+pub const FPS: u32 = 60;
+
+pub fn approx_eq(a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    (a - b).norm() < 0.01
+}
+
+// A game world, updated at `FPS`
+pub fn new_world<'a, 'b>() -> GameWorld<'a, 'b> {
+    let world = GameWorld::new();
+    *world.entity_world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(1. / FPS as f32);
+
+    world
+}
+
+pub fn color() -> Color {
+    Color::new(1., 1., 1., 1.)
+}
+
+// A player 32 pixels wide, at (X + x, y)
+pub fn add_player(world: &mut GameWorld, x: f32, y: f32) -> Entity {
+    world
+        .add_game_entity(Player::new(
+            Point2::new(X + x, y),
+            Point2::new(32., 32.),
+            color(),
+        ))
+        .entity()
+}
+
+// A body 32 pixels wide, at (X + x, y)
+pub fn add_body(world: &mut GameWorld, x: f32, y: f32, body: RigidBody) -> Entity {
+    world
+        .add_game_entity(Body::new(
+            Point2::new(X + x, y),
+            Point2::new(32., 32.),
+            body,
+        ))
+        .entity()
+}
+
+// Puts the entity in the trigger zone, as if it had just entered it
+pub fn enter(world: &mut GameWorld, zone: Entity, entity: Entity) {
+    world
+        .entity_world
+        .write::<Trigger>()
+        .get_mut(zone)
+        .unwrap()
+        .enter(entity);
+    world
+        .entity_world
+        .write_resource::<TriggerEvents>()
+        .push(TriggerEvent::Enter(zone, entity));
+}
+
+// Where the entity is, relatively to (X, 0)
+pub fn position(world: &GameWorld, entity: Entity) -> Vector2<f32> {
+    world
+        .entity_world
+        .read::<Transform>()
+        .get(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+        - Vector2::new(X, 0.)
+}
+
+pub fn velocity(world: &GameWorld, entity: Entity) -> Vector2<f32> {
+    world
+        .entity_world
+        .read::<Moving>()
+        .get(entity)
+        .unwrap()
+        .velocity
+        .vector
+}
+
+pub fn set_velocity(world: &mut GameWorld, entity: Entity, velocity: Vector2<f32>) {
+    world
+        .entity_world
+        .write::<Moving>()
+        .get_mut(entity)
+        .unwrap()
+        .velocity
+        .vector = velocity;
+}
+
+// Moves the entities for a second: the motion is the velocity, plus half the acceleration
+pub fn step(world: &mut GameWorld) {
+    world
+        .entity_world
+        .write_resource::<DeltaTime>()
+        .set(Duration::from_secs(1));
+    SysMoving {}.run_now(&world.entity_world.res);
+}
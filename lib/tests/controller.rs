@@ -0,0 +1,269 @@
+extern crate some_platformer_lib as lib;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::CollisionLayer;
+use lib::components::collider::Collider;
+use lib::components::controller::CharacterController;
+use lib::components::grounded::Grounded;
+use lib::components::input::Input;
+use lib::components::jump::Jump;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::transform::Transform;
+use lib::nalgebra::{Point2, Translation2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::{Entity, RunNow, World};
+use lib::systems::sys_controller::SysController;
+use lib::systems::sys_grounded::SysGrounded;
+use lib::systems::sys_moving::SysMoving;
+use lib::systems::sys_moving_collision::SysMovingCollision;
+use lib::world::gameworld::GameWorld;
+
+const FPS: u32 = 60;
+
+fn new_world() -> World {
+    let world = GameWorld::new().entity_world;
+    *world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(1. / FPS as f32);
+    world
+}
+
+fn add_box(world: &mut World, x: f32, y: f32, w: f32, h: f32) {
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(w, h)))
+        .build();
+    add_collider(world, entity, CollisionLayer::Terrain);
+}
+
+fn add_collider(world: &mut World, entity: Entity, layer: CollisionLayer) {
+    let collider = {
+        let transforms = world.read::<Transform>();
+        world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            transforms.get(entity).unwrap(),
+            layer,
+        )
+    };
+    world.write::<Collider>().insert(entity, collider);
+}
+
+fn add_player(world: &mut World, x: f32, y: f32) -> Entity {
+    let controller = CharacterController::new();
+    let jump = Jump::new(controller.jump_speed());
+
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(32., 32.)))
+        .with(Moving::new())
+        .with(Grounded::new())
+        .with(jump)
+        .with(Input::new())
+        .with(controller)
+        .build();
+    add_collider(world, entity, CollisionLayer::Player);
+
+    // Knows what it touches from the start
+    SysGrounded {}.run_now(&world.res);
+
+    entity
+}
+
+// A player standing on a wide ground (its top is at y = 90)
+fn on_the_ground() -> (World, Entity) {
+    let mut world = new_world();
+    add_box(&mut world, 0., 100., 2000., 20.);
+    let player = add_player(&mut world, 0., 74.);
+
+    (world, player)
+}
+
+// A player pulled up, standing on a wide ceiling (its bottom is at y = -90)
+fn on_the_ceiling() -> (World, Entity) {
+    let mut world = new_world();
+    add_box(&mut world, 0., -100., 2000., 20.);
+    let player = add_player(&mut world, 0., -74.);
+    let flipped = GravityAffected::new().with_direction(Vector2::new(0., -1.));
+    world.write::<GravityAffected>().insert(player, flipped);
+    SysGrounded {}.run_now(&world.res);
+
+    (world, player)
+}
+
+// A player in the air, touching a wall on its left
+fn against_a_wall() -> (World, Entity) {
+    let mut world = new_world();
+    add_box(&mut world, -26., 0., 20., 2000.);
+    let player = add_player(&mut world, 0., 0.);
+
+    (world, player)
+}
+
+fn step(world: &mut World, player: Entity, input: Input) {
+    *world.write::<Input>().get_mut(player).unwrap() = input;
+
+    SysController {}.run_now(&world.res);
+    SysMoving {}.run_now(&world.res);
+    SysMovingCollision {}.run_now(&world.res);
+    SysGrounded {}.run_now(&world.res);
+}
+
+fn is_dashing(world: &World, player: Entity) -> bool {
+    world
+        .read::<CharacterController>()
+        .get(player)
+        .unwrap()
+        .is_dashing()
+}
+
+fn position(world: &World, entity: Entity) -> Vector2<f32> {
+    world
+        .read::<Transform>()
+        .get(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+}
+
+fn velocity(world: &World, entity: Entity) -> Vector2<f32> {
+    world.read::<Moving>().get(entity).unwrap().velocity.vector
+}
+
+fn jump() -> Input {
+    Input {
+        jump: true,
+        ..Input::new()
+    }
+}
+
+// Jumps, holding the button for the given number of updates, and returns the jump height
+fn jump_height(hold: u32) -> f32 {
+    let (mut world, player) = on_the_ground();
+    let start = position(&world, player).y;
+
+    let mut highest = start;
+    for frame in 0..FPS {
+        step(
+            &mut world,
+            player,
+            if frame < hold { jump() } else { Input::new() },
+        );
+        highest = highest.min(position(&world, player).y);
+    }
+
+    start - highest
+}
+
+#[test]
+fn jump_tuning_sets_the_gravity_and_speed() {
+    let controller = CharacterController::new().with_jump(120., 0.4);
+
+    assert!((controller.gravity() - 1500.).abs() < 0.01);
+    assert!((controller.jump_speed() - 600.).abs() < 0.01);
+}
+
+#[test]
+fn full_jump_reaches_the_jump_height() {
+    assert!((jump_height(FPS) - 120.).abs() < 1.);
+}
+
+#[test]
+fn releasing_early_makes_a_shorter_jump() {
+    let height = jump_height(6);
+
+    assert!(height > 40.);
+    assert!(height < 100.);
+}
+
+#[test]
+fn stands_and_jumps_on_the_ceiling_when_pulled_up() {
+    let (mut world, player) = on_the_ceiling();
+    let on_ground = |world: &World| world.read::<Grounded>().get(player).unwrap().on_ground;
+    assert!(on_ground(&world));
+
+    // Held against it by its gravity
+    step(&mut world, player, Input::new());
+    assert!(on_ground(&world));
+    assert!((position(&world, player).y + 74.).abs() < 0.01);
+
+    // Jumps away from it (down), as high as on the ground, then falls back on it
+    step(&mut world, player, jump());
+    assert!(velocity(&world, player).y > 0.);
+    let mut lowest = position(&world, player).y;
+    for _ in 0..FPS {
+        step(&mut world, player, jump());
+        lowest = lowest.max(position(&world, player).y);
+    }
+
+    assert!((lowest + 74. - 120.).abs() < 1.);
+    assert!(on_ground(&world));
+    assert!((position(&world, player).y + 74.).abs() < 0.01);
+}
+
+#[test]
+fn runs_up_to_the_run_speed_then_stops() {
+    let (mut world, player) = on_the_ground();
+    let right = Input {
+        right: true,
+        ..Input::new()
+    };
+
+    step(&mut world, player, right);
+    assert!((velocity(&world, player).x - 50.).abs() < 0.01);
+
+    for _ in 0..FPS / 2 {
+        step(&mut world, player, right);
+    }
+    assert!((velocity(&world, player).x - 300.).abs() < 0.01);
+
+    for _ in 0..FPS / 2 {
+        step(&mut world, player, Input::new());
+    }
+    assert!(velocity(&world, player).x.abs() < 0.01);
+}
+
+#[test]
+fn slides_down_walls() {
+    let (mut world, player) = against_a_wall();
+    world.write::<Moving>().get_mut(player).unwrap().velocity = Translation2::new(0., 500.);
+    let left = Input {
+        left: true,
+        ..Input::new()
+    };
+
+    step(&mut world, player, left);
+
+    assert_eq!(velocity(&world, player), Vector2::new(0., 100.));
+}
+
+#[test]
+fn jumps_off_walls() {
+    let (mut world, player) = against_a_wall();
+
+    step(&mut world, player, jump());
+
+    let velocity = velocity(&world, player);
+    assert!((velocity.x - 300.).abs() < 0.01);
+    assert!(velocity.y < 0.);
+}
+
+#[test]
+fn dashes_forward_then_cools_down() {
+    let (mut world, player) = on_the_ground();
+    let dash = Input {
+        dash: true,
+        ..Input::new()
+    };
+
+    step(&mut world, player, dash);
+    assert_eq!(velocity(&world, player), Vector2::new(900., 0.));
+    assert!(is_dashing(&world, player));
+
+    for _ in 0..FPS / 5 {
+        step(&mut world, player, Input::new());
+    }
+    assert!(!is_dashing(&world, player));
+
+    // Still cooling down
+    step(&mut world, player, dash);
+    assert!(!is_dashing(&world, player));
+}
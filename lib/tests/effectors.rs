@@ -0,0 +1,291 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{add_body, approx_eq, color, enter, position, set_velocity, step, velocity, X};
+use lib::components::effector::AreaEffector;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::rigid_body::RigidBody;
+use lib::entities::ground::Ground;
+use lib::entities::trigger::TriggerZone;
+use lib::map::{EffectorData, Map};
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::resources::trigger_events::TriggerEvents;
+use lib::specs::{Entity, Join, RunNow};
+use lib::systems::sys_effectors::SysEffectors;
+use lib::systems::sys_grounded::SysGrounded;
+use lib::world::gameworld::GameWorld;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[test]
+fn forces_and_impulses_accumulate_until_applied() {
+    let mut moving = Moving::new();
+    moving.add_force(Vector2::new(100., 0.));
+    moving.add_force(Vector2::new(0., -50.));
+    moving.add_impulse(Vector2::new(20., 0.));
+    moving.add_impulse(Vector2::new(20., 10.));
+
+    // For a mass of 2
+    moving.apply_forces(0.5);
+    assert!(approx_eq(moving.acceleration, Vector2::new(50., -25.)));
+    assert!(approx_eq(moving.velocity.vector, Vector2::new(20., 5.)));
+
+    // Applied only once
+    moving.apply_forces(0.5);
+    assert!(approx_eq(moving.acceleration, Vector2::new(50., -25.)));
+    assert!(approx_eq(moving.velocity.vector, Vector2::new(20., 5.)));
+}
+
+fn add_effector(
+    world: &mut GameWorld,
+    x: f32,
+    y: f32,
+    size: Point2<f32>,
+    effector: AreaEffector,
+) -> Entity {
+    world
+        .add_game_entity(TriggerZone::effector(Point2::new(X + x, y), size, effector))
+        .entity()
+}
+
+fn moving(world: &GameWorld, entity: Entity) -> (Vector2<f32>, Vector2<f32>) {
+    let moving = world.entity_world.read::<Moving>();
+    let mov = moving.get(entity).unwrap();
+
+    (mov.force, mov.impulse)
+}
+
+#[test]
+fn forces_and_impulses_are_integrated_with_mass() {
+    let mut world = GameWorld::new();
+    let light = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    let heavy = add_body(&mut world, 0., 100., RigidBody::new(2.));
+    let kicked = add_body(&mut world, 0., 200., RigidBody::new(4.));
+    {
+        let mut moving = world.entity_world.write::<Moving>();
+        moving
+            .get_mut(light)
+            .unwrap()
+            .add_force(Vector2::new(100., 0.));
+        moving
+            .get_mut(heavy)
+            .unwrap()
+            .add_force(Vector2::new(100., 0.));
+        moving
+            .get_mut(kicked)
+            .unwrap()
+            .add_impulse(Vector2::new(100., 0.));
+    }
+
+    step(&mut world);
+
+    assert!(approx_eq(position(&world, light), Vector2::new(50., 0.)));
+    assert!(approx_eq(velocity(&world, light), Vector2::new(100., 0.)));
+    assert!(approx_eq(position(&world, heavy), Vector2::new(25., 100.)));
+    assert!(approx_eq(velocity(&world, heavy), Vector2::new(50., 0.)));
+    assert!(approx_eq(position(&world, kicked), Vector2::new(25., 200.)));
+    assert!(approx_eq(velocity(&world, kicked), Vector2::new(25., 0.)));
+}
+
+#[test]
+fn wind_pushes_the_entities_inside() {
+    let mut world = GameWorld::new();
+    let size = Point2::new(400., 400.);
+    let wind = add_effector(
+        &mut world,
+        0.,
+        0.,
+        size,
+        AreaEffector::Wind { force: (300., 0.) },
+    );
+    let inside = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    let outside = add_body(&mut world, 1000., 0., RigidBody::new(1.));
+    enter(&mut world, wind, inside);
+
+    // Every update, as long as they're inside
+    for _ in 0..2 {
+        SysEffectors {}.run_now(&world.entity_world.res);
+    }
+
+    assert_eq!(
+        moving(&world, inside),
+        (Vector2::new(600., 0.), Vector2::new(0., 0.))
+    );
+    assert_eq!(
+        moving(&world, outside),
+        (Vector2::new(0., 0.), Vector2::new(0., 0.))
+    );
+}
+
+#[test]
+fn bounce_pads_launch_the_entities_entering_them() {
+    let mut world = GameWorld::new();
+    let size = Point2::new(100., 20.);
+    let pad = add_effector(
+        &mut world,
+        0.,
+        0.,
+        size,
+        AreaEffector::BouncePad {
+            impulse: (0., -800.),
+        },
+    );
+    let body = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    set_velocity(&mut world, body, Vector2::new(50., 300.));
+    enter(&mut world, pad, body);
+
+    SysEffectors {}.run_now(&world.entity_world.res);
+
+    // The fall is stopped, not the sideways motion
+    assert!(approx_eq(velocity(&world, body), Vector2::new(50., 0.)));
+    assert_eq!(
+        moving(&world, body),
+        (Vector2::new(0., 0.), Vector2::new(0., -800.))
+    );
+
+    // Only when entering
+    world.entity_world.write_resource::<TriggerEvents>().clear();
+    world
+        .entity_world
+        .write::<Moving>()
+        .get_mut(body)
+        .unwrap()
+        .apply_forces(1.);
+    SysEffectors {}.run_now(&world.entity_world.res);
+    assert_eq!(moving(&world, body).1, Vector2::new(0., 0.));
+}
+
+#[test]
+fn explosions_knock_the_entities_back() {
+    let mut world = GameWorld::new();
+    let size = Point2::new(200., 200.);
+    let explosion = add_effector(
+        &mut world,
+        0.,
+        0.,
+        size,
+        AreaEffector::Explosion { strength: 500. },
+    );
+    let right = add_body(&mut world, 50., 0., RigidBody::new(1.));
+    let above = add_body(&mut world, 0., -50., RigidBody::new(1.));
+    enter(&mut world, explosion, right);
+    enter(&mut world, explosion, above);
+
+    SysEffectors {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(moving(&world, right).1, Vector2::new(500., 0.)));
+    assert!(approx_eq(moving(&world, above).1, Vector2::new(0., -500.)));
+}
+
+#[test]
+fn conveyors_carry_the_entities_standing_on_them() {
+    let mut world = GameWorld::new();
+    // Its top is at y = 90
+    world.add_game_entity(Ground::new(
+        Point2::new(X, 100.),
+        Point2::new(400., 20.),
+        color(),
+    ));
+    let size = Point2::new(400., 20.);
+    let conveyor = add_effector(
+        &mut world,
+        0.,
+        80.,
+        size,
+        AreaEffector::Conveyor { speed: -100. },
+    );
+    let standing = add_body(&mut world, 0., 74., RigidBody::new(1.));
+    let falling = add_body(&mut world, 100., 60., RigidBody::new(1.));
+    enter(&mut world, conveyor, standing);
+    enter(&mut world, conveyor, falling);
+
+    world
+        .entity_world
+        .write_resource::<DeltaTime>()
+        .set(Duration::from_millis(100));
+    SysGrounded {}.run_now(&world.entity_world.res);
+    SysEffectors {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(
+        position(&world, standing),
+        Vector2::new(-10., 74.)
+    ));
+    assert!(approx_eq(
+        position(&world, falling),
+        Vector2::new(100., 60.)
+    ));
+}
+
+#[test]
+fn conveyors_carry_along_the_ground_of_the_entities() {
+    let mut world = GameWorld::new();
+    // A wall whose left side is at x = 90
+    world.add_game_entity(Ground::new(
+        Point2::new(X + 100., 0.),
+        Point2::new(20., 400.),
+        color(),
+    ));
+    let size = Point2::new(20., 400.);
+    let conveyor = add_effector(
+        &mut world,
+        80.,
+        0.,
+        size,
+        AreaEffector::Conveyor { speed: -100. },
+    );
+    // Pulled right, against the wall: its right is down the screen
+    let standing = add_body(&mut world, 74., 0., RigidBody::new(1.));
+    world.entity_world.write::<GravityAffected>().insert(
+        standing,
+        GravityAffected::new().with_direction(Vector2::new(1., 0.)),
+    );
+    enter(&mut world, conveyor, standing);
+
+    world
+        .entity_world
+        .write_resource::<DeltaTime>()
+        .set(Duration::from_millis(100));
+    SysGrounded {}.run_now(&world.entity_world.res);
+    SysEffectors {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(
+        position(&world, standing),
+        Vector2::new(74., -10.)
+    ));
+}
+
+#[test]
+fn maps_spawn_their_effectors() {
+    let mut world = GameWorld::new();
+    let map = Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: vec![
+            EffectorData {
+                position: (0., 0.),
+                size: (100., 100.),
+                effector: AreaEffector::Wind { force: (0., -100.) },
+            },
+            EffectorData {
+                position: (200., 0.),
+                size: (100., 20.),
+                effector: AreaEffector::Conveyor { speed: 50. },
+            },
+        ],
+        water: Vec::new(),
+    };
+    let count = |world: &GameWorld| {
+        let effectors = world.entity_world.read::<AreaEffector>();
+        (&effectors).join().count()
+    };
+
+    world.set_map(map.clone());
+    assert_eq!(count(&world), 2);
+
+    // Replacing the map replaces its effectors
+    world.set_map(map);
+    assert_eq!(count(&world), 2);
+}
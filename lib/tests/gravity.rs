@@ -0,0 +1,215 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{add_body, add_player, approx_eq, position, X};
+
+use lib::components::controller::CharacterController;
+use lib::components::gravity_zone::GravityZone;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::rigid_body::RigidBody;
+use lib::components::trigger::{Trigger, TriggerKind};
+use lib::entities::trigger::TriggerZone;
+use lib::nalgebra::{Point2, Vector2};
+use lib::specs::{Entity, RunNow, World};
+use lib::systems::sys_controller::SysController;
+use lib::systems::sys_gravity_zones::SysGravityZones;
+use lib::systems::sys_moving::SysMovingGravity;
+use lib::world::gameworld::GameWorld;
+use std::time::Duration;
+
+#[test]
+fn entities_scale_and_turn_the_gravity() {
+    let world_gravity = Vector2::new(0., 1500.);
+
+    let affected = GravityAffected::new();
+    assert_eq!(affected.gravity(world_gravity), world_gravity);
+
+    let light = GravityAffected::new().with_scale(0.5);
+    assert!(approx_eq(
+        light.gravity(world_gravity),
+        Vector2::new(0., 750.)
+    ));
+
+    // Only the direction changes, not the strength
+    let flipped = GravityAffected::new().with_direction(Vector2::new(0., -2.));
+    assert!(approx_eq(
+        flipped.gravity(world_gravity),
+        Vector2::new(0., -1500.)
+    ));
+
+    let floating = GravityAffected::new().with_scale(0.);
+    assert!(approx_eq(
+        floating.gravity(world_gravity),
+        Vector2::new(0., 0.)
+    ));
+}
+
+#[test]
+fn zones_change_the_gravity_of_the_entities_inside() {
+    let world_gravity = Vector2::new(0., 1500.);
+
+    // The zone's scale adds to the entity's
+    let mut light = GravityAffected::new().with_scale(0.5);
+    light.set_zone(Some(GravityZone::new(0.2)));
+    assert!(approx_eq(
+        light.gravity(world_gravity),
+        Vector2::new(0., 150.)
+    ));
+
+    // The zone's direction replaces the entity's
+    let mut sideways = GravityAffected::new().with_direction(Vector2::new(0., -1.));
+    sideways.set_zone(Some(
+        GravityZone::new(1.).with_direction(Vector2::new(1., 0.)),
+    ));
+    assert!(approx_eq(
+        sideways.gravity(world_gravity),
+        Vector2::new(1500., 0.)
+    ));
+
+    sideways.set_zone(None);
+    assert!(approx_eq(
+        sideways.gravity(world_gravity),
+        Vector2::new(0., -1500.)
+    ));
+}
+
+// The SPECS world of a game, without its systems
+fn new_world() -> World {
+    GameWorld::new().entity_world
+}
+
+fn add_affected(world: &mut World, affected: GravityAffected) -> Entity {
+    world
+        .create_entity()
+        .with(Moving::new())
+        .with(affected)
+        .build()
+}
+
+fn add_zone(world: &mut World, zone: GravityZone, inside: &[Entity]) -> Entity {
+    let mut trigger = Trigger::new(TriggerKind::Gravity);
+    for &entity in inside {
+        trigger.enter(entity);
+    }
+
+    world.create_entity().with(trigger).with(zone).build()
+}
+
+fn acceleration(world: &World, entity: Entity) -> Vector2<f32> {
+    world.read::<Moving>().get(entity).unwrap().acceleration
+}
+
+#[test]
+fn gravity_accelerates_each_entity_by_its_own() {
+    let mut world = new_world();
+    let falling = add_affected(&mut world, GravityAffected::new());
+    let floating = add_affected(&mut world, GravityAffected::new().with_scale(0.));
+    let rising = add_affected(
+        &mut world,
+        GravityAffected::new().with_direction(Vector2::new(0., -1.)),
+    );
+    // Left to its controller
+    let controlled = add_affected(&mut world, GravityAffected::new());
+    world
+        .write::<CharacterController>()
+        .insert(controlled, CharacterController::new());
+
+    SysMovingGravity {}.run_now(&world.res);
+
+    assert!(approx_eq(
+        acceleration(&world, falling),
+        Vector2::new(0., 1500.)
+    ));
+    assert!(approx_eq(
+        acceleration(&world, floating),
+        Vector2::new(0., 0.)
+    ));
+    assert!(approx_eq(
+        acceleration(&world, rising),
+        Vector2::new(0., -1500.)
+    ));
+    assert!(approx_eq(
+        acceleration(&world, controlled),
+        Vector2::new(0., 0.)
+    ));
+}
+
+#[test]
+fn the_zone_of_highest_priority_applies() {
+    let mut world = new_world();
+    let inside_both = add_affected(&mut world, GravityAffected::new());
+    let inside_one = add_affected(&mut world, GravityAffected::new());
+    let outside = add_affected(&mut world, GravityAffected::new());
+
+    let low = GravityZone::new(0.5);
+    let flipped = GravityZone::new(1.)
+        .with_direction(Vector2::new(0., -1.))
+        .with_priority(1);
+    add_zone(&mut world, low, &[inside_both, inside_one]);
+    add_zone(&mut world, flipped, &[inside_both]);
+
+    SysGravityZones {}.run_now(&world.res);
+    SysMovingGravity {}.run_now(&world.res);
+
+    let affected = world.read::<GravityAffected>();
+    assert_eq!(affected.get(inside_both).unwrap().zone(), Some(&flipped));
+    assert_eq!(affected.get(inside_one).unwrap().zone(), Some(&low));
+    assert_eq!(affected.get(outside).unwrap().zone(), None);
+
+    assert!(approx_eq(
+        acceleration(&world, inside_both),
+        Vector2::new(0., -1500.)
+    ));
+    assert!(approx_eq(
+        acceleration(&world, inside_one),
+        Vector2::new(0., 750.)
+    ));
+    assert!(approx_eq(
+        acceleration(&world, outside),
+        Vector2::new(0., 1500.)
+    ));
+}
+
+#[test]
+fn bodies_float_in_zero_gravity_zones() {
+    let mut world = GameWorld::new();
+    world.add_game_entity(TriggerZone::gravity(
+        Point2::new(X, 0.),
+        Point2::new(400., 400.),
+        GravityZone::new(0.),
+    ));
+    let inside = add_body(&mut world, 0., 0., RigidBody::default());
+    let outside = add_body(&mut world, 1000., 0., RigidBody::default());
+
+    // The zone is entered once the collisions are detected, during the first update
+    for _ in 0..10 {
+        world.update_by(Duration::from_millis(16));
+    }
+
+    let affected = world.entity_world.read::<GravityAffected>();
+    assert!(affected.get(inside).unwrap().zone().is_some());
+    assert!(affected.get(outside).unwrap().zone().is_none());
+    assert!(position(&world, inside).y < position(&world, outside).y / 4.);
+}
+
+#[test]
+fn characters_gravity_is_scaled_too() {
+    let mut world = GameWorld::new();
+    let player = add_player(&mut world, 0., 0.);
+    world
+        .entity_world
+        .write::<GravityAffected>()
+        .get_mut(player)
+        .unwrap()
+        .scale = 0.5;
+
+    SysController {}.run_now(&world.entity_world.res);
+
+    let gravity = CharacterController::new().gravity();
+    let moving = world.entity_world.read::<Moving>();
+    assert!(approx_eq(
+        moving.get(player).unwrap().acceleration,
+        Vector2::new(0., gravity * 0.5)
+    ));
+}
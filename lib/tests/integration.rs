@@ -0,0 +1,172 @@
+extern crate some_platformer_lib as lib;
+
+use lib::components::grounded::Grounded;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::transform::Transform;
+use lib::nalgebra::{Point2, Translation2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::resources::gravity::Gravity;
+use lib::specs::RunNow;
+use lib::systems::sys_moving::{SysMoving, SysMovingGravity};
+use lib::world::gameworld::GameWorld;
+use std::time::Duration;
+
+// Simulates an entity without collider for a second, at the given frame rate
+//
+// Returns its final position and velocity.
+fn simulate(fps: u32, moving: Moving, on_ground: bool) -> (Vector2<f32>, Vector2<f32>) {
+    simulate_affected(fps, moving, on_ground, GravityAffected::new())
+}
+
+// Like `simulate`, for an entity with its own gravity
+fn simulate_affected(
+    fps: u32,
+    moving: Moving,
+    on_ground: bool,
+    affected: GravityAffected,
+) -> (Vector2<f32>, Vector2<f32>) {
+    let mut world = GameWorld::new().entity_world;
+    *world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(1. / fps as f32);
+    *world.write_resource::<Gravity>() = Gravity::new_custom(0., 1000.);
+
+    let mut grounded = Grounded::new();
+    grounded.on_ground = on_ground;
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(0., 0.), Point2::new(32., 32.)))
+        .with(moving)
+        .with(affected)
+        .with(grounded)
+        .build();
+
+    for _ in 0..fps {
+        SysMovingGravity {}.run_now(&world.res);
+        SysMoving {}.run_now(&world.res);
+    }
+
+    let position = world
+        .read::<Transform>()
+        .get(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector;
+    let velocity = world.read::<Moving>().get(entity).unwrap().velocity.vector;
+
+    (position, velocity)
+}
+
+fn approx_eq(a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    (a - b).norm() < 0.5
+}
+
+#[test]
+fn delta_time_is_in_milliseconds() {
+    let mut delta_time = DeltaTime::new();
+    delta_time.set(Duration::from_millis(16));
+
+    assert_eq!(delta_time.delta_ns, 16_000_000);
+    assert!((delta_time.delta_ms - 16.).abs() < 0.01);
+    assert!((delta_time.seconds() - 0.016).abs() < 0.0001);
+}
+
+#[test]
+fn falling_is_frame_rate_independent() {
+    let (slow_position, slow_velocity) = simulate(30, Moving::new(), false);
+    let (fast_position, fast_velocity) = simulate(144, Moving::new(), false);
+
+    // Falls by g * t^2 / 2 in a second, at any frame rate
+    assert!(approx_eq(slow_position, Vector2::new(0., 500.)));
+    assert!(approx_eq(fast_position, Vector2::new(0., 500.)));
+    assert!(approx_eq(slow_velocity, Vector2::new(0., 1000.)));
+    assert!(approx_eq(fast_velocity, Vector2::new(0., 1000.)));
+}
+
+#[test]
+fn horizontal_speed_is_in_pixels_per_second() {
+    let mut moving = Moving::new();
+    moving.velocity = Translation2::new(200., 0.);
+
+    let (slow_position, _) = simulate(30, moving, false);
+    assert!((slow_position.x - 200.).abs() < 0.5);
+}
+
+#[test]
+fn falling_stops_accelerating_at_terminal_velocity() {
+    let slow = simulate(30, Moving::new().with_terminal_velocity(400.), false);
+    let fast = simulate(144, Moving::new().with_terminal_velocity(400.), false);
+
+    // Accelerates for 0.4s (80px), then falls at 400px/s for 0.6s (240px)
+    assert!(approx_eq(slow.0, Vector2::new(0., 320.)));
+    assert!(approx_eq(fast.0, Vector2::new(0., 320.)));
+    assert_eq!(slow.1, Vector2::new(0., 400.));
+    assert_eq!(fast.1, Vector2::new(0., 400.));
+}
+
+#[test]
+fn terminal_velocity_is_along_the_gravity() {
+    let flipped = || GravityAffected::new().with_direction(Vector2::new(0., -1.));
+    let moving = || Moving::new().with_terminal_velocity(400.);
+
+    let slow = simulate_affected(30, moving(), false, flipped());
+    let fast = simulate_affected(144, moving(), false, flipped());
+
+    // Falls up, like `falling_stops_accelerating_at_terminal_velocity` falls down
+    assert!(approx_eq(slow.0, Vector2::new(0., -320.)));
+    assert!(approx_eq(fast.0, Vector2::new(0., -320.)));
+    assert!(approx_eq(slow.1, Vector2::new(0., -400.)));
+    assert!(approx_eq(fast.1, Vector2::new(0., -400.)));
+}
+
+#[test]
+fn air_drag_is_frame_rate_independent() {
+    let moving = || {
+        let mut moving = Moving::new().with_drag(0.5);
+        moving.velocity = Translation2::new(300., 0.);
+        moving
+    };
+
+    let (slow_position, slow_velocity) = simulate(30, moving(), false);
+    let (fast_position, fast_velocity) = simulate(144, moving(), false);
+
+    // Decays towards the speed at which the drag cancels the gravity (2000px/s downwards)
+    let decay = (-0.5f32).exp();
+    let velocity = Vector2::new(300. * decay, 2000. * (1. - decay));
+    let position = Vector2::new(600. * (1. - decay), 2000. - 4000. * (1. - decay));
+    assert!(approx_eq(slow_velocity, velocity));
+    assert!(approx_eq(fast_velocity, velocity));
+    assert!(approx_eq(slow_position, position));
+    assert!(approx_eq(fast_position, position));
+}
+
+#[test]
+fn air_drag_slows_down_the_fall_to_terminal_velocity() {
+    let moving = || Moving::new().with_drag(0.5).with_terminal_velocity(400.);
+
+    let slow = simulate(30, moving(), false);
+    let fast = simulate(144, moving(), false);
+
+    assert_eq!(slow.1, Vector2::new(0., 400.));
+    assert_eq!(fast.1, Vector2::new(0., 400.));
+    assert!(approx_eq(slow.0, fast.0));
+    // Slower than without drag (see `falling_stops_accelerating_at_terminal_velocity`)
+    assert!(slow.0.y < 320.);
+}
+
+#[test]
+fn ground_friction_stops_the_entity() {
+    let moving = || {
+        let mut moving = Moving::new().with_friction(400.);
+        moving.velocity = Translation2::new(-300., 0.);
+        moving
+    };
+
+    let (slow_position, slow_velocity) = simulate(30, moving(), true);
+    let (fast_position, fast_velocity) = simulate(144, moving(), true);
+
+    // Stopped after 0.75s (112.5px), without going backwards
+    assert!(slow_velocity.x.abs() < 0.01);
+    assert!(fast_velocity.x.abs() < 0.01);
+    assert!((slow_position.x + 112.5).abs() < 0.5);
+    assert!((fast_position.x + 112.5).abs() < 0.5);
+}
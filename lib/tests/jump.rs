@@ -0,0 +1,221 @@
+extern crate some_platformer_lib as lib;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::CollisionLayer;
+use lib::components::collider::Collider;
+use lib::components::grounded::Grounded;
+use lib::components::jump::Jump;
+use lib::components::moving::Moving;
+use lib::components::transform::Transform;
+use lib::map::{Block, Map};
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::{Entity, RunNow, World};
+use lib::systems::sys_grounded::SysGrounded;
+use lib::systems::sys_jump::SysJump;
+use lib::world::gameworld::GameWorld;
+
+// A 60 FPS update, in nanoseconds
+const FRAME_NS: u64 = 16_666_667;
+
+fn new_world(blocks: &[(i32, i32)]) -> World {
+    let world = GameWorld::new().entity_world;
+    *world.write_resource::<Map>() = Map {
+        elements: blocks
+            .iter()
+            .map(|&p| (p, Block { one_way: false }))
+            .collect(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    };
+    world.write_resource::<DeltaTime>().delta_ns = FRAME_NS;
+    world
+}
+
+fn add_collider(world: &mut World, entity: Entity, layer: CollisionLayer) {
+    let collider = {
+        let transforms = world.read::<Transform>();
+        world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            transforms.get(entity).unwrap(),
+            layer,
+        )
+    };
+    world.write::<Collider>().insert(entity, collider);
+}
+
+fn add_player(world: &mut World, x: f32, y: f32) -> Entity {
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(32., 32.)))
+        .with(Moving::new())
+        .with(Grounded::new())
+        .with(Jump::new(10.))
+        .build();
+    add_collider(world, entity, CollisionLayer::Player);
+
+    entity
+}
+
+fn update_grounded(world: &mut World) {
+    SysGrounded {}.run_now(&world.res);
+}
+
+#[test]
+fn standing_on_a_tile_is_grounded() {
+    // The tile (0, 0) covers x in [400, 440] and y in [560, 600]
+    let mut world = new_world(&[(0, 0)]);
+    let player = add_player(&mut world, 420., 544.);
+
+    update_grounded(&mut world);
+
+    let grounded = world.read::<Grounded>();
+    let grounded = grounded.get(player).unwrap();
+    assert!(grounded.on_ground);
+    assert!(!grounded.on_wall());
+    assert!(grounded.time_since_grounded.abs() < 1e-4);
+}
+
+#[test]
+fn touching_a_tile_on_the_side_is_on_a_wall() {
+    // The tile (1, 1) covers x in [440, 480] and y in [520, 560]
+    let mut world = new_world(&[(1, 1)]);
+    let player = add_player(&mut world, 424., 540.);
+
+    update_grounded(&mut world);
+
+    let grounded = world.read::<Grounded>();
+    let grounded = grounded.get(player).unwrap();
+    assert!(!grounded.on_ground);
+    assert!(!grounded.on_wall_left);
+    assert!(grounded.on_wall_right);
+}
+
+#[test]
+fn standing_on_a_static_collider_is_grounded() {
+    let mut world = new_world(&[]);
+    let ground = world
+        .create_entity()
+        .with(Transform::new(
+            Vector2::new(0., 100.),
+            Point2::new(200., 20.),
+        ))
+        .build();
+    add_collider(&mut world, ground, CollisionLayer::Terrain);
+    let wall = world
+        .create_entity()
+        .with(Transform::new(
+            Vector2::new(-26., 74.),
+            Point2::new(20., 32.),
+        ))
+        .build();
+    add_collider(&mut world, wall, CollisionLayer::Terrain);
+    let player = add_player(&mut world, 0., 74.);
+
+    update_grounded(&mut world);
+
+    let grounded = world.read::<Grounded>();
+    let grounded = grounded.get(player).unwrap();
+    assert!(grounded.on_ground);
+    assert!(grounded.on_wall_left);
+    assert!(!grounded.on_wall_right);
+}
+
+#[test]
+fn time_since_grounded_grows_in_the_air() {
+    let mut world = new_world(&[(0, 0)]);
+    let player = add_player(&mut world, 420., 544.);
+    update_grounded(&mut world);
+
+    // Move up, away from the ground
+    world
+        .write::<Transform>()
+        .get_mut(player)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+        .y = 500.;
+    update_grounded(&mut world);
+    update_grounded(&mut world);
+
+    let grounded = world.read::<Grounded>();
+    let grounded = grounded.get(player).unwrap();
+    assert!(!grounded.on_ground);
+    assert!((grounded.time_since_grounded - 2. * FRAME_NS as f32 / 1e9).abs() < 1e-4);
+}
+
+#[test]
+fn jumps_from_the_ground() {
+    let mut jump = Jump::new(10.);
+
+    // Not pressed
+    assert!(!jump.update(true, 0., 0.016));
+
+    jump.press();
+    assert!(jump.update(true, 0., 0.016));
+    assert!(!jump.is_buffered());
+}
+
+#[test]
+fn jumps_during_coyote_time() {
+    let mut jump = Jump::new(10.).with_windows(0.1, 0.1);
+    jump.update(true, 0., 0.016);
+
+    // Just walked off a ledge
+    jump.press();
+    assert!(jump.update(false, 0.05, 0.016));
+
+    // But only once
+    jump.press();
+    assert!(!jump.update(false, 0.066, 0.016));
+}
+
+#[test]
+fn does_not_jump_after_coyote_time() {
+    let mut jump = Jump::new(10.).with_windows(0.1, 0.1);
+    jump.update(true, 0., 0.016);
+
+    jump.press();
+    assert!(!jump.update(false, 0.2, 0.016));
+}
+
+#[test]
+fn buffered_press_jumps_when_landing() {
+    let mut jump = Jump::new(10.).with_windows(0.1, 0.1);
+
+    // Pressed while falling, a few frames before landing
+    jump.press();
+    assert!(!jump.update(false, 1., 0.03));
+    assert!(!jump.update(false, 1.03, 0.03));
+    assert!(jump.is_buffered());
+    assert!(jump.update(true, 0., 0.03));
+}
+
+#[test]
+fn buffered_press_expires() {
+    let mut jump = Jump::new(10.).with_windows(0.1, 0.1);
+
+    jump.press();
+    for i in 0..4 {
+        assert!(!jump.update(false, 1. + i as f32 * 0.03, 0.03));
+    }
+
+    assert!(!jump.is_buffered());
+    assert!(!jump.update(true, 0., 0.03));
+}
+
+#[test]
+fn jump_system_sets_the_upward_velocity() {
+    let mut world = new_world(&[(0, 0)]);
+    let player = add_player(&mut world, 420., 544.);
+    update_grounded(&mut world);
+
+    world.write::<Jump>().get_mut(player).unwrap().press();
+    SysJump {}.run_now(&world.res);
+
+    let moving = world.read::<Moving>();
+    assert!((moving.get(player).unwrap().velocity.vector.y + 10.).abs() < 0.01);
+}
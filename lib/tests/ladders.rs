@@ -0,0 +1,305 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{add_player, approx_eq, color, enter, new_world, position, velocity, FPS, X};
+use lib::components::climbable::Climbable;
+use lib::components::controller::CharacterController;
+use lib::components::grounded::Grounded;
+use lib::components::input::Input;
+use lib::components::moving::GravityAffected;
+use lib::components::trigger::Trigger;
+use lib::entities::ground::Ground;
+use lib::entities::trigger::TriggerZone;
+use lib::nalgebra::{Point2, Vector2};
+use lib::specs::{Entity, RunNow};
+use lib::systems::sys_controller::SysController;
+use lib::systems::sys_grounded::SysGrounded;
+use lib::systems::sys_moving::SysMoving;
+use lib::world::gameworld::GameWorld;
+
+// A player inside a climbable area 32 pixels wide, from y = -100 to y = 100, a bit right of
+// its center
+fn next_to<'a, 'b>(climbable: Climbable, y: f32) -> (GameWorld<'a, 'b>, Entity, Entity) {
+    let mut world = new_world();
+    let area = world
+        .add_game_entity(TriggerZone::climbable(
+            Point2::new(X, 0.),
+            Point2::new(32., 200.),
+            climbable,
+        ))
+        .entity();
+    let player = add_player(&mut world, 8., y);
+
+    // Overlapping it, as detected by the collisions
+    enter(&mut world, area, player);
+
+    (world, area, player)
+}
+
+fn step(world: &mut GameWorld, player: Entity, input: Input) {
+    *world.entity_world.write::<Input>().get_mut(player).unwrap() = input;
+
+    SysController {}.run_now(&world.entity_world.res);
+    SysMoving {}.run_now(&world.entity_world.res);
+    SysGrounded {}.run_now(&world.entity_world.res);
+}
+
+// Some ground, centered on (X + x, y)
+fn ground(x: f32, y: f32, w: f32, h: f32) -> Ground {
+    Ground::new(Point2::new(X + x, y), Point2::new(w, h), color())
+}
+
+fn is_climbing(world: &GameWorld, player: Entity) -> bool {
+    world
+        .entity_world
+        .read::<CharacterController>()
+        .get(player)
+        .unwrap()
+        .is_climbing()
+}
+
+fn up() -> Input {
+    Input {
+        up: true,
+        ..Input::new()
+    }
+}
+
+#[test]
+fn only_climbs_once_grabbed() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+
+    step(&mut world, player, Input::new());
+
+    assert!(!is_climbing(&world, player));
+    assert!(velocity(&world, player).y > 0.);
+}
+
+#[test]
+fn climbs_ladders_without_gravity_snapped_to_their_center() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+
+    for _ in 0..10 {
+        step(&mut world, player, up());
+    }
+
+    let speed = CharacterController::new().climb_speed;
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(
+        velocity(&world, player),
+        Vector2::new(0., -speed)
+    ));
+    assert!(approx_eq(
+        position(&world, player),
+        Vector2::new(0., 50. - speed * 10. / FPS as f32)
+    ));
+
+    // Stays in place when nothing is held
+    step(&mut world, player, Input::new());
+    let held = position(&world, player);
+    step(&mut world, player, Input::new());
+
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(position(&world, player), held));
+}
+
+#[test]
+fn grabs_going_down_in_the_air() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    let down = Input {
+        down: true,
+        ..Input::new()
+    };
+
+    step(&mut world, player, down);
+
+    let speed = CharacterController::new().climb_speed;
+    assert!(is_climbing(&world, player));
+    assert!((velocity(&world, player).y - speed).abs() < 0.01);
+}
+
+#[test]
+fn climbs_vines_sideways() {
+    let (mut world, _, player) = next_to(Climbable::vines(), 50.);
+    let up_right = Input {
+        right: true,
+        ..up()
+    };
+
+    step(&mut world, player, up_right);
+
+    let speed = CharacterController::new().climb_speed;
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(
+        velocity(&world, player),
+        Vector2::new(speed, -speed)
+    ));
+    assert!(position(&world, player).x > 8.);
+}
+
+#[test]
+fn jumps_off() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    step(&mut world, player, up());
+
+    let jump_right = Input {
+        jump: true,
+        right: true,
+        ..Input::new()
+    };
+    step(&mut world, player, jump_right);
+
+    let controller = CharacterController::new();
+    assert!(!is_climbing(&world, player));
+    assert!(approx_eq(
+        velocity(&world, player),
+        Vector2::new(controller.run_speed, -controller.jump_speed())
+    ));
+}
+
+#[test]
+fn climbs_and_jumps_off_along_its_own_gravity() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    // Pulled up: the ladder's top is its bottom edge, at y = 100
+    world
+        .entity_world
+        .write::<GravityAffected>()
+        .insert(
+            player,
+            GravityAffected::new().with_direction(Vector2::new(0., -1.)),
+        );
+
+    for _ in 0..10 {
+        step(&mut world, player, up());
+    }
+
+    let controller = CharacterController::new();
+    let speed = controller.climb_speed;
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(
+        position(&world, player),
+        Vector2::new(0., 50. + speed * 10. / FPS as f32)
+    ));
+
+    let jump_right = Input {
+        jump: true,
+        right: true,
+        ..Input::new()
+    };
+    step(&mut world, player, jump_right);
+
+    assert!(!is_climbing(&world, player));
+    assert!(approx_eq(
+        velocity(&world, player),
+        Vector2::new(controller.run_speed, controller.jump_speed())
+    ));
+}
+
+#[test]
+fn climbing_past_the_top_puts_it_on_top() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), -60.);
+
+    step(&mut world, player, up());
+    for _ in 0..FPS {
+        if !is_climbing(&world, player) {
+            break;
+        }
+        step(&mut world, player, up());
+    }
+
+    // Standing on the top of the ladder, 100 pixels above its center
+    assert!(!is_climbing(&world, player));
+    assert!(approx_eq(position(&world, player), Vector2::new(0., -116.)));
+    assert!(approx_eq(velocity(&world, player), Vector2::new(0., 0.)));
+}
+
+#[test]
+fn lets_go_at_the_bottom() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    step(&mut world, player, up());
+    world
+        .entity_world
+        .write::<Grounded>()
+        .get_mut(player)
+        .unwrap()
+        .on_ground = true;
+
+    let down = Input {
+        down: true,
+        ..Input::new()
+    };
+    step(&mut world, player, down);
+
+    assert!(!is_climbing(&world, player));
+}
+
+#[test]
+fn lets_go_when_leaving_the_area() {
+    let (mut world, area, player) = next_to(Climbable::ladder(), 50.);
+    step(&mut world, player, up());
+    world
+        .entity_world
+        .write::<Trigger>()
+        .get_mut(area)
+        .unwrap()
+        .exit(player);
+
+    step(&mut world, player, up());
+
+    assert!(!is_climbing(&world, player));
+    assert!(velocity(&world, player).y > -CharacterController::new().climb_speed);
+}
+
+#[test]
+fn snapping_is_stopped_by_walls() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    // Touching the player's left side, between it and the ladder's center
+    world.add_game_entity(ground(-18., 0., 20., 400.));
+
+    for _ in 0..10 {
+        step(&mut world, player, up());
+    }
+
+    let speed = CharacterController::new().climb_speed;
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(
+        position(&world, player),
+        Vector2::new(8., 50. - speed * 10. / FPS as f32)
+    ));
+}
+
+#[test]
+fn climbing_is_stopped_by_ceilings() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), 50.);
+    // Across the ladder, its bottom at y = 20
+    world.add_game_entity(ground(0., 10., 200., 20.));
+
+    for _ in 0..FPS {
+        step(&mut world, player, up());
+    }
+
+    assert!(is_climbing(&world, player));
+    assert!(approx_eq(position(&world, player), Vector2::new(0., 36.)));
+}
+
+#[test]
+fn climbs_out_onto_the_floor_at_the_top_and_stays_on_it() {
+    let (mut world, _, player) = next_to(Climbable::ladder(), -60.);
+    // The ladder goes through it, its top level with the ladder's
+    world.add_game_entity(ground(0., -90., 200., 20.).one_way());
+
+    // Up held all along: once out, it doesn't fall back in nor grab the ladder again
+    let mut climbed_out = false;
+    for _ in 0..FPS {
+        step(&mut world, player, up());
+        assert!(!(climbed_out && is_climbing(&world, player)));
+        climbed_out = !is_climbing(&world, player);
+    }
+
+    assert!(climbed_out);
+    assert!(approx_eq(position(&world, player), Vector2::new(0., -116.)));
+    assert!(approx_eq(velocity(&world, player), Vector2::new(0., 0.)));
+    let grounded = world.entity_world.read::<Grounded>();
+    assert!(grounded.get(player).unwrap().on_ground);
+}
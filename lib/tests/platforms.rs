@@ -0,0 +1,291 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::approx_eq;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::CollisionLayer;
+use lib::components::collider::Collider;
+use lib::components::moving::{GravityAffected, Moving};
+use lib::components::platform::{Easing, MovingPlatform, PathMode};
+use lib::components::transform::Transform;
+use lib::entities::game_entity::GameEntity;
+use lib::entities::platform::Platform;
+use lib::map::{Map, MapData, PlatformData};
+use lib::nalgebra::{Point2, Translation2, Vector2};
+use lib::resources::crush_events::{CrushEvent, CrushEvents};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::{Entity, RunNow, World};
+use lib::systems::sys_platform::SysPlatforms;
+use lib::world::gameworld::GameWorld;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn waypoints(points: &[(f32, f32)]) -> Vec<Vector2<f32>> {
+    points.iter().map(|&(x, y)| Vector2::new(x, y)).collect()
+}
+
+#[test]
+fn platforms_loop_along_their_path() {
+    let path = waypoints(&[(0., 0.), (100., 0.), (100., 100.)]);
+    let mut platform = MovingPlatform::new(0, path, 100.);
+
+    assert!(approx_eq(platform.advance(0.5), Vector2::new(50., 0.)));
+    assert!(approx_eq(platform.advance(1.), Vector2::new(100., 50.)));
+    assert!(approx_eq(platform.velocity, Vector2::new(50., 50.)));
+
+    // Back from the last waypoint to the first one
+    let diagonal = 100. * 2f32.sqrt();
+    assert!(approx_eq(
+        platform.advance(0.5 + diagonal / 200.),
+        Vector2::new(50., 50.)
+    ));
+}
+
+#[test]
+fn platforms_ping_pong_along_their_path() {
+    let path = waypoints(&[(0., 0.), (100., 0.)]);
+    let mut platform = MovingPlatform::new(0, path, 100.).with_mode(PathMode::PingPong);
+
+    assert!(approx_eq(platform.advance(1.5), Vector2::new(50., 0.)));
+    assert!(!platform.state().forward);
+    assert!(approx_eq(platform.velocity, Vector2::new(100. / 3., 0.)));
+
+    assert!(approx_eq(platform.advance(1.), Vector2::new(50., 0.)));
+    assert!(platform.state().forward);
+}
+
+#[test]
+fn platforms_ease_between_waypoints() {
+    let path = waypoints(&[(0., 0.), (100., 0.)]);
+    let mut platform = MovingPlatform::new(0, path, 100.).with_easing(Easing::EaseInOut);
+
+    // Slow at first, then fast in the middle
+    assert!(approx_eq(platform.advance(0.25), Vector2::new(15.625, 0.)));
+    assert!(approx_eq(platform.advance(0.25), Vector2::new(50., 0.)));
+}
+
+#[test]
+fn platform_states_can_be_replicated() {
+    let path = waypoints(&[(0., 0.), (100., 0.), (100., 100.)]);
+    let mut server = MovingPlatform::new(3, path.clone(), 100.);
+    let mut client = MovingPlatform::new(3, path, 100.);
+
+    server.advance(1.25);
+    client.set_state(&server.state());
+
+    assert_eq!(client.state(), server.state());
+    assert!(approx_eq(client.position(), Vector2::new(100., 25.)));
+}
+
+fn new_world() -> World {
+    let world = GameWorld::new().entity_world;
+    *world.write_resource::<Map>() = Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    };
+    *world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(0.1);
+    world
+}
+
+fn add_platform(world: &mut World, waypoints: Vec<(f32, f32)>) -> Entity {
+    let data = PlatformData {
+        size: (100., 20.),
+        waypoints,
+        speed: 100.,
+        mode: PathMode::PingPong,
+        easing: Easing::Linear,
+        one_way: false,
+    };
+
+    Platform::new(0, data).add_to_world(world).entity()
+}
+
+fn add_static(world: &mut World, x: f32, y: f32, w: f32, h: f32) {
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(w, h)))
+        .build();
+    add_collider(world, entity, CollisionLayer::Terrain);
+}
+
+fn add_moving(world: &mut World, x: f32, y: f32, vx: f32) -> Entity {
+    let mut moving = Moving::new();
+    moving.velocity = Translation2::new(vx, 0.);
+
+    let entity = world
+        .create_entity()
+        .with(Transform::new(Vector2::new(x, y), Point2::new(32., 32.)))
+        .with(moving)
+        .build();
+    add_collider(world, entity, CollisionLayer::Player);
+
+    entity
+}
+
+fn add_collider(world: &mut World, entity: Entity, layer: CollisionLayer) {
+    let collider = {
+        let transforms = world.read::<Transform>();
+        world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            transforms.get(entity).unwrap(),
+            layer,
+        )
+    };
+    world.write::<Collider>().insert(entity, collider);
+}
+
+fn position(world: &World, entity: Entity) -> Vector2<f32> {
+    world
+        .read::<Transform>()
+        .get(entity)
+        .unwrap()
+        .isometry
+        .translation
+        .vector
+}
+
+#[test]
+fn platforms_carry_their_riders() {
+    let mut world = new_world();
+    // Its top is at y = 90
+    let platform = add_platform(&mut world, vec![(0., 100.), (1000., 100.)]);
+    let rider = add_moving(&mut world, 0., 74., 0.);
+    let bystander = add_moving(&mut world, 0., 0., 0.);
+
+    SysPlatforms {}.run_now(&world.res);
+
+    assert!(approx_eq(
+        position(&world, platform),
+        Vector2::new(10., 100.)
+    ));
+    assert!(approx_eq(position(&world, rider), Vector2::new(10., 74.)));
+    assert!(approx_eq(position(&world, bystander), Vector2::new(0., 0.)));
+}
+
+#[test]
+fn platforms_carry_the_riders_hanging_under_them_upside_down() {
+    let mut world = new_world();
+    // Its bottom is at y = 110
+    add_platform(&mut world, vec![(0., 100.), (1000., 100.)]);
+    let rider = add_moving(&mut world, 0., 126., 0.);
+    world.write::<GravityAffected>().insert(
+        rider,
+        GravityAffected::new().with_direction(Vector2::new(0., -1.)),
+    );
+    // Pulled down, it only touches the platform with its head
+    let bystander = add_moving(&mut world, 0., 126., 0.);
+
+    SysPlatforms {}.run_now(&world.res);
+
+    assert!(approx_eq(position(&world, rider), Vector2::new(10., 126.)));
+    assert!(approx_eq(
+        position(&world, bystander),
+        Vector2::new(0., 126.)
+    ));
+}
+
+#[test]
+fn platforms_push_what_is_in_their_way() {
+    let mut world = new_world();
+    // Its right side is at x = 50
+    add_platform(&mut world, vec![(0., 100.), (1000., 100.)]);
+    let pushed = add_moving(&mut world, 66., 100., -50.);
+
+    SysPlatforms {}.run_now(&world.res);
+
+    assert!(approx_eq(position(&world, pushed), Vector2::new(76., 100.)));
+    let moving = world.read::<Moving>();
+    assert!(moving.get(pushed).unwrap().velocity.vector.x.abs() < 0.01);
+    assert!(world.read_resource::<CrushEvents>().is_empty());
+}
+
+#[test]
+fn platforms_crush_riders_against_ceilings() {
+    let mut world = new_world();
+    // Going up, its top is at y = 90
+    let platform = add_platform(&mut world, vec![(0., 100.), (0., 0.)]);
+    let rider = add_moving(&mut world, 0., 74., 0.);
+    // The ceiling's bottom touches the rider's top, at y = 58
+    add_static(&mut world, 0., 48., 200., 20.);
+
+    SysPlatforms {}.run_now(&world.res);
+
+    let events: Vec<CrushEvent> = world
+        .read_resource::<CrushEvents>()
+        .iter()
+        .cloned()
+        .collect();
+    assert_eq!(
+        events,
+        vec![CrushEvent {
+            entity: rider,
+            platform,
+        }]
+    );
+}
+
+#[test]
+fn maps_spawn_their_moving_platforms() {
+    let mut world = GameWorld::new();
+    let map = Map {
+        elements: HashMap::new(),
+        moving_platforms: vec![PlatformData {
+            size: (100., 20.),
+            waypoints: vec![(0., 100.), (100., 100.)],
+            speed: 100.,
+            mode: PathMode::Loop,
+            easing: Easing::Linear,
+            one_way: true,
+        }],
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    };
+
+    world.set_map(map.clone());
+    assert_eq!(world.platform_states().len(), 1);
+
+    // Replacing the map replaces its platforms
+    world.set_map(map);
+    assert_eq!(world.platform_states().len(), 1);
+    world.set_map(Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    });
+    assert!(world.platform_states().is_empty());
+}
+
+#[test]
+fn maps_are_sent_with_their_moving_platforms() {
+    let mut server = GameWorld::new();
+    let mut client = GameWorld::new();
+    let map = Map {
+        moving_platforms: vec![PlatformData {
+            size: (100., 20.),
+            waypoints: vec![(0., 100.), (100., 100.)],
+            speed: 100.,
+            mode: PathMode::PingPong,
+            easing: Easing::Linear,
+            one_way: false,
+        }],
+        ..Map::default()
+    };
+
+    // As sent by the server, the client spawning its platforms from it
+    let data = MapData::from(&map);
+    assert_eq!(MapData::from(&Map::from(data.clone())), data);
+    server.set_map(map);
+    client.set_map(Map::from(data));
+
+    server.update_by(Duration::from_millis(500));
+    client.set_platform_states(&server.platform_states());
+    assert_eq!(client.platform_states(), server.platform_states());
+}
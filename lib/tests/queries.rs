@@ -0,0 +1,181 @@
+extern crate some_platformer_lib as lib;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::CollisionLayer;
+use lib::collision::queries::QueryFilter;
+use lib::components::transform::Transform;
+use lib::nalgebra::{Point2, Vector2};
+use lib::specs::{Entity, World};
+
+fn new_world() -> World {
+    let mut world = World::new();
+    world.add_resource(CollisionHandler::new());
+    world
+}
+
+fn add_box(world: &mut World, layer: CollisionLayer, x: f32, y: f32, w: f32, h: f32) -> Entity {
+    let entity = world.create_entity().build();
+    let transform = Transform::new(Vector2::new(x, y), Point2::new(w, h));
+
+    let mut handler = world.write_resource::<CollisionHandler>();
+    handler.add_cuboid(entity, &transform, layer);
+    handler.world.update();
+
+    entity
+}
+
+fn approx_eq(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.01
+}
+
+#[test]
+fn raycast_hits_the_closest_collider() {
+    let mut world = new_world();
+    let near = add_box(&mut world, CollisionLayer::Terrain, 0., 100., 200., 20.);
+    add_box(&mut world, CollisionLayer::Terrain, 0., 200., 200., 20.);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    let layers = [CollisionLayer::Terrain];
+    let hit = handler
+        .raycast(
+            Point2::new(0., 0.),
+            Vector2::new(0., 1.),
+            500.,
+            QueryFilter::layers(&layers),
+        )
+        .unwrap();
+
+    assert_eq!(hit.entity, near);
+    assert!(approx_eq(hit.distance, 90.));
+    assert!(approx_eq(hit.point.y, 90.));
+    assert!(approx_eq(hit.normal.y, -1.));
+
+    let all = handler.raycast_all(
+        Point2::new(0., 0.),
+        Vector2::new(0., 1.),
+        500.,
+        QueryFilter::layers(&layers),
+    );
+    assert_eq!(all.len(), 2);
+    assert!(all[0].distance < all[1].distance);
+}
+
+#[test]
+fn raycast_respects_the_distance_and_layers() {
+    let mut world = new_world();
+    let player = add_box(&mut world, CollisionLayer::Player, 0., 0., 32., 32.);
+    add_box(&mut world, CollisionLayer::Enemy, 0., 60., 32., 32.);
+    let ground = add_box(&mut world, CollisionLayer::Terrain, 0., 100., 200., 20.);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    let down = Vector2::new(0., 1.);
+    let terrain = [CollisionLayer::Terrain];
+
+    // Too short to reach the ground
+    assert!(handler
+        .raycast(
+            Point2::new(0., 0.),
+            down,
+            50.,
+            QueryFilter::layers(&terrain)
+        )
+        .is_none());
+
+    // The enemy in between is ignored
+    let hit = handler
+        .raycast(
+            Point2::new(0., 0.),
+            down,
+            500.,
+            QueryFilter::layers(&terrain),
+        )
+        .unwrap();
+    assert_eq!(hit.entity, ground);
+
+    // The ray starts inside the player, which is excluded
+    let layers = [CollisionLayer::Player, CollisionLayer::Terrain];
+    let hit = handler
+        .raycast(
+            Point2::new(0., 0.),
+            down,
+            500.,
+            QueryFilter::layers(&layers).excluding(player),
+        )
+        .unwrap();
+    assert_eq!(hit.entity, ground);
+}
+
+#[test]
+fn shape_cast_stops_against_the_first_collider() {
+    let mut world = new_world();
+    let wall = add_box(&mut world, CollisionLayer::Terrain, 100., 0., 20., 200.);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    let layers = [CollisionLayer::Terrain];
+    let hit = handler
+        .shape_cast(
+            Vector2::new(32., 32.),
+            Point2::new(0., 0.),
+            Vector2::new(200., 0.),
+            QueryFilter::layers(&layers),
+        )
+        .unwrap();
+
+    // The box's right side (at x = 16) reaches the wall's left side (at x = 90)
+    assert_eq!(hit.entity, wall);
+    assert!(approx_eq(hit.time * 200., 74.));
+    assert!(approx_eq(hit.point.x, 90.));
+    assert!(approx_eq(hit.normal.x, -1.));
+
+    // Not moving far enough
+    assert!(handler
+        .shape_cast(
+            Vector2::new(32., 32.),
+            Point2::new(0., 0.),
+            Vector2::new(50., 0.),
+            QueryFilter::layers(&layers),
+        )
+        .is_none());
+}
+
+#[test]
+fn shape_cast_without_motion_hits_nothing() {
+    let mut world = new_world();
+    // Touching the box
+    add_box(&mut world, CollisionLayer::Terrain, 26., 0., 20., 200.);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    let layers = [CollisionLayer::Terrain];
+    assert!(handler
+        .shape_cast(
+            Vector2::new(32., 32.),
+            Point2::new(0., 0.),
+            Vector2::new(0., 0.),
+            QueryFilter::layers(&layers),
+        )
+        .is_none());
+}
+
+#[test]
+fn overlapping_returns_the_entities_in_the_area() {
+    let mut world = new_world();
+    let ground = add_box(&mut world, CollisionLayer::Terrain, 0., 100., 200., 20.);
+    let enemy = add_box(&mut world, CollisionLayer::Enemy, 50., 70., 32., 32.);
+    add_box(&mut world, CollisionLayer::Enemy, 500., 70., 32., 32.);
+
+    let handler = world.read_resource::<CollisionHandler>();
+    let area = (Point2::new(0., 50.), Point2::new(100., 95.));
+
+    let layers = [CollisionLayer::Terrain, CollisionLayer::Enemy];
+    let mut found = handler.overlapping(area.0, area.1, QueryFilter::layers(&layers));
+    found.sort();
+    let mut expected = vec![ground, enemy];
+    expected.sort();
+    assert_eq!(found, expected);
+
+    let layers = [CollisionLayer::Enemy];
+    assert_eq!(
+        handler.overlapping(area.0, area.1, QueryFilter::layers(&layers)),
+        vec![enemy]
+    );
+}
@@ -0,0 +1,273 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::approx_eq;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::{CollisionLayer, InteractionMatrix};
+use lib::collision::oriented::{self, OrientedRect};
+use lib::collision::statics::{self, StaticCollider};
+use lib::components::collider::Collider;
+use lib::components::moving::Moving;
+use lib::components::transform::Transform;
+use lib::map::Map;
+use lib::nalgebra::{Isometry2, Point2, Translation2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::{Entity, RunNow, World};
+use lib::systems::sys_moving::SysMoving;
+use lib::systems::sys_moving_collision::SysMovingCollision;
+use lib::types::Rect;
+use lib::world::gameworld::GameWorld;
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+fn approx_eq_rect(a: Rect, b: Rect) -> bool {
+    (a - b).norm() < 0.01
+}
+
+fn turned(x: f32, y: f32, w: f32, h: f32, angle: f32) -> Transform {
+    Transform::new_with_isometry(Isometry2::new(Vector2::new(x, y), angle), Point2::new(w, h))
+}
+
+// A square of 100 pixels turned by an eighth of a turn, centered on the origin: its top
+// right side lies on x + y = 50√2
+fn diamond() -> Transform {
+    turned(0., 0., 100., 100., FRAC_PI_4)
+}
+
+fn square(x: f32, y: f32) -> OrientedRect {
+    OrientedRect::from_rect(&Rect::new(x - 10., y - 10., 20., 20.))
+}
+
+#[test]
+fn bounds_are_the_rect_when_not_turned() {
+    let transform = Transform::new(Vector2::new(10., 20.), Point2::new(64., 32.));
+
+    assert_eq!(transform.aabb(), transform.as_rect());
+    assert!(transform.oriented_rect().is_axis_aligned());
+}
+
+#[test]
+fn quarter_turns_swap_the_bounds() {
+    let transform = turned(10., 20., 64., 32., FRAC_PI_2);
+
+    assert!(transform.oriented_rect().is_axis_aligned());
+    assert!(approx_eq_rect(
+        transform.aabb(),
+        Rect::new(-6., -12., 32., 64.)
+    ));
+}
+
+#[test]
+fn corners_turn_with_the_entity() {
+    let transform = turned(0., 0., 2., 2., FRAC_PI_4);
+    let corners = transform.corners();
+
+    let expected = [
+        Vector2::new(0., -SQRT_2),
+        Vector2::new(SQRT_2, 0.),
+        Vector2::new(0., SQRT_2),
+        Vector2::new(-SQRT_2, 0.),
+    ];
+    for (corner, expected) in corners.iter().zip(expected.iter()) {
+        assert!(approx_eq(corner.coords, *expected));
+    }
+    assert!(!transform.oriented_rect().is_axis_aligned());
+    assert!(approx_eq_rect(
+        transform.aabb(),
+        Rect::new(-SQRT_2, -SQRT_2, 2. * SQRT_2, 2. * SQRT_2)
+    ));
+}
+
+#[test]
+fn turned_rectangles_overlap_by_their_shape_not_their_bounds() {
+    let diamond = diamond().oriented_rect();
+
+    // Inside the bounds of the diamond, but outside of it
+    assert_eq!(oriented::penetration(&square(55., 55.), &diamond), None);
+
+    // Pushed out through the side it went into
+    let push = oriented::penetration(&square(40., 40.), &diamond).unwrap();
+    let depth = 50. - (40. * SQRT_2 - 10. * SQRT_2);
+    assert!(approx_eq(push, Vector2::new(1., 1.) * (depth / SQRT_2)));
+}
+
+#[test]
+fn turned_rectangles_are_hit_on_their_side() {
+    let diamond = diamond().oriented_rect();
+
+    // Falling on its top corner, 50√2 above its center
+    let (time, normal) =
+        oriented::time_of_impact(&square(0., -100.), Vector2::new(0., 100.), &diamond).unwrap();
+    assert!((time - (90. - 50. * SQRT_2) / 100.).abs() < 0.001);
+    assert_eq!(normal, Vector2::new(0., -1.));
+
+    // Missing it
+    assert_eq!(
+        oriented::time_of_impact(&square(100., -100.), Vector2::new(0., 100.), &diamond),
+        None
+    );
+}
+
+#[test]
+fn sweeps_slide_along_turned_colliders() {
+    let diamond = diamond();
+    let collider = StaticCollider {
+        rect: diamond.aabb(),
+        shape: diamond.oriented_rect(),
+        layer: CollisionLayer::Terrain,
+        one_way: false,
+    };
+    let rect = Rect::new(40., -60., 20., 20.);
+
+    // Falling on its top right side, then sliding down along it
+    let sweep = statics::sweep(
+        &[collider],
+        &InteractionMatrix::default(),
+        CollisionLayer::Player,
+        &rect,
+        Vector2::new(0., 40.),
+        Vector2::new(0., 1.),
+        false,
+    );
+
+    // Touching it after falling by 80 - 50√2, then sliding diagonally
+    let hit = 80. - 50. * SQRT_2;
+    let slide = (40. - hit) / 2.;
+    assert!(approx_eq(sweep.motion, Vector2::new(slide, hit + slide)));
+    assert!(sweep.blocked_y);
+}
+
+// The SPECS world of a game without its systems, nor tiles, updated at 60 FPS
+fn new_world() -> World {
+    let world = GameWorld::new().entity_world;
+    *world.write_resource::<Map>() = Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    };
+    *world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(1. / 60.);
+    world
+}
+
+// A turned diamond collider, like `diamond`
+fn add_diamond(world: &mut World) {
+    let ground = world.create_entity().with(diamond()).build();
+    add_collider(world, ground, CollisionLayer::Terrain);
+}
+
+fn add_moving(world: &mut World, transform: Transform, velocity: Vector2<f32>) -> Entity {
+    let mut moving = Moving::new();
+    moving.velocity = Translation2::from_vector(velocity);
+    let entity = world.create_entity().with(transform).with(moving).build();
+    add_collider(world, entity, CollisionLayer::Player);
+
+    entity
+}
+
+fn add_collider(world: &mut World, entity: Entity, layer: CollisionLayer) {
+    let collider = {
+        let transforms = world.read::<Transform>();
+        world.write_resource::<CollisionHandler>().add_cuboid(
+            entity,
+            transforms.get(entity).unwrap(),
+            layer,
+        )
+    };
+    world.write::<Collider>().insert(entity, collider);
+}
+
+#[test]
+fn entities_are_pushed_out_of_turned_colliders_along_their_side() {
+    let mut world = new_world();
+    add_diamond(&mut world);
+    let entity = add_moving(
+        &mut world,
+        Transform::new(Vector2::new(40., 40.), Point2::new(20., 20.)),
+        Vector2::new(-100., 0.),
+    );
+
+    SysMovingCollision {}.run_now(&world.res);
+
+    // Out of the diamond, only keeping the velocity along its side
+    let depth = 50. - (40. * SQRT_2 - 10. * SQRT_2);
+    let transforms = world.read::<Transform>();
+    let tr = transforms.get(entity).unwrap();
+    assert!(approx_eq(
+        tr.isometry.translation.vector,
+        Vector2::new(40., 40.) + Vector2::new(1., 1.) * (depth / SQRT_2)
+    ));
+    let penetration = oriented::penetration(&tr.oriented_rect(), &diamond().oriented_rect());
+    assert!(penetration.map_or(0., |push| push.norm()) < 0.01);
+    let moving = world.read::<Moving>();
+    assert!(approx_eq(
+        moving.get(entity).unwrap().velocity.vector,
+        Vector2::new(-50., 50.)
+    ));
+}
+
+#[test]
+fn entities_slide_along_turned_colliders_at_the_speed_along_their_side() {
+    let mut world = new_world();
+    add_diamond(&mut world);
+    // Falling by 40 pixels within the update, like in `sweeps_slide_along_turned_colliders`
+    let entity = add_moving(
+        &mut world,
+        Transform::new(Vector2::new(50., -50.), Point2::new(20., 20.)),
+        Vector2::new(0., 2400.),
+    );
+
+    SysMoving {}.run_now(&world.res);
+
+    let hit = 80. - 50. * SQRT_2;
+    let slide = (40. - hit) / 2.;
+    let transforms = world.read::<Transform>();
+    assert!(approx_eq(
+        transforms.get(entity).unwrap().isometry.translation.vector,
+        Vector2::new(50. + slide, -50. + hit + slide)
+    ));
+    let moving = world.read::<Moving>();
+    assert!(approx_eq(
+        moving.get(entity).unwrap().velocity.vector,
+        Vector2::new(1200., 1200.)
+    ));
+}
+
+#[test]
+fn turned_entities_collide_as_their_bounds() {
+    let mut world = new_world();
+    add_diamond(&mut world);
+    // A small diamond 15 pixels away from the top right side of the big one: its corners
+    // are 10 pixels away from its center, its bounds 20 pixels away along that side's normal
+    let normal = Vector2::new(1., -1.) / SQRT_2;
+    let entity = add_moving(
+        &mut world,
+        turned(normal.x * 65., normal.y * 65., 20., 20., FRAC_PI_4),
+        Vector2::new(0., 0.),
+    );
+
+    // Its shape doesn't touch the big one: the collision world, which has the actual shapes,
+    // sees no contact between them
+    {
+        let mut handler = world.write_resource::<CollisionHandler>();
+        handler.world.update();
+        let contacts = handler
+            .world
+            .contact_pairs()
+            .filter(|&(_, _, contacts)| contacts.num_contacts() > 0)
+            .count();
+        assert_eq!(contacts, 0);
+    }
+
+    SysMovingCollision {}.run_now(&world.res);
+
+    // Pushed out by its bounds, as they would have been stopped while moving
+    let transforms = world.read::<Transform>();
+    assert!(approx_eq(
+        transforms.get(entity).unwrap().isometry.translation.vector,
+        normal * 70.
+    ));
+}
@@ -0,0 +1,168 @@
+extern crate some_platformer_lib as lib;
+
+use lib::collision::collision_handling::CollisionHandler;
+use lib::collision::layers::CollisionLayer;
+use lib::collision::tiles::sweep;
+use lib::components::collider::Collider;
+use lib::components::moving::Moving;
+use lib::components::transform::Transform;
+use lib::map::{self, Block, Map, TILE_SIZE};
+use lib::nalgebra::{Point2, Translation2, Vector2};
+use lib::resources::delta_time::DeltaTime;
+use lib::specs::RunNow;
+use lib::systems::sys_moving::SysMoving;
+use lib::types::Rect;
+use lib::world::gameworld::GameWorld;
+
+fn new_map(blocks: &[(i32, i32)], platforms: &[(i32, i32)]) -> Map {
+    let blocks = blocks.iter().map(|&p| (p, Block { one_way: false }));
+    let platforms = platforms.iter().map(|&p| (p, Block { one_way: true }));
+
+    Map {
+        elements: blocks.chain(platforms).collect(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: Vec::new(),
+    }
+}
+
+#[test]
+fn tiles_share_the_render_grid() {
+    assert_eq!(
+        map::tile_rect((-10, 14)),
+        Rect::new(0., 0., TILE_SIZE, TILE_SIZE)
+    );
+    assert_eq!(
+        map::tile_rect((0, 0)),
+        Rect::new(400., 560., TILE_SIZE, TILE_SIZE)
+    );
+    assert_eq!(
+        map::tile_at_cell(map::cell_at(410.), map::cell_at(570.)),
+        (0, 0)
+    );
+}
+
+#[test]
+fn lands_on_a_tile() {
+    // The tile top is at y = 560
+    let map = new_map(&[(0, 0)], &[]);
+    let rect = Rect::new(404., 520., 32., 32.);
+
+    let sweep = sweep(&map, &rect, Vector2::new(0., 20.), false);
+
+    assert_eq!(sweep.motion, Vector2::new(0., 8.));
+    assert!(!sweep.blocked_x);
+    assert!(sweep.blocked_y);
+}
+
+#[test]
+fn stops_against_a_tile_wall() {
+    // The tile left side is at x = 400
+    let map = new_map(&[(0, 1), (0, 2)], &[]);
+    let rect = Rect::new(360., 500., 32., 32.);
+
+    let sweep = sweep(&map, &rect, Vector2::new(20., 0.), false);
+
+    assert_eq!(sweep.motion, Vector2::new(8., 0.));
+    assert!(sweep.blocked_x);
+    assert!(!sweep.blocked_y);
+}
+
+#[test]
+fn slides_along_the_ground() {
+    let map = new_map(&[(0, 0), (1, 0)], &[]);
+    // Standing exactly on the ground, the tiles below don't block horizontally
+    let rect = Rect::new(404., 528., 32., 32.);
+
+    let sweep = sweep(&map, &rect, Vector2::new(20., 5.), false);
+
+    assert_eq!(sweep.motion, Vector2::new(20., 0.));
+    assert!(!sweep.blocked_x);
+    assert!(sweep.blocked_y);
+}
+
+#[test]
+fn fast_moves_do_not_tunnel() {
+    // A single tile, far below
+    let map = new_map(&[(0, 0)], &[]);
+    let rect = Rect::new(404., 0., 32., 32.);
+
+    let sweep = sweep(&map, &rect, Vector2::new(0., 2000.), false);
+
+    assert_eq!(sweep.motion, Vector2::new(0., 528.));
+    assert!(sweep.blocked_y);
+}
+
+#[test]
+fn one_way_tiles_only_block_from_above() {
+    let map = new_map(&[], &[(0, 0)]);
+
+    // Falling on it
+    let above = Rect::new(404., 520., 32., 32.);
+    let landing = sweep(&map, &above, Vector2::new(0., 20.), false);
+    assert_eq!(landing.motion, Vector2::new(0., 8.));
+
+    // Dropping through it
+    let dropping = sweep(&map, &above, Vector2::new(0., 20.), true);
+    assert_eq!(dropping.motion, Vector2::new(0., 20.));
+
+    // Jumping through it
+    let below = Rect::new(404., 610., 32., 32.);
+    let jumping = sweep(&map, &below, Vector2::new(0., -40.), false);
+    assert_eq!(jumping.motion, Vector2::new(0., -40.));
+
+    // Walking through it
+    let beside = Rect::new(360., 570., 32., 32.);
+    let walking = sweep(&map, &beside, Vector2::new(20., 0.), false);
+    assert_eq!(walking.motion, Vector2::new(20., 0.));
+}
+
+#[test]
+fn moving_entities_collide_with_the_map() {
+    let mut world = GameWorld::new().entity_world;
+    *world.write_resource::<Map>() = new_map(&[(0, 0)], &[]);
+    // A one second update: the motion is the velocity
+    *world.write_resource::<DeltaTime>() = DeltaTime::from_seconds(1.);
+
+    let transform = Transform::new(Vector2::new(420., 536.), Point2::new(32., 32.));
+    let mut moving = Moving::new();
+    moving.velocity = Translation2::new(0., 20.);
+    let player = world.create_entity().with(moving).build();
+    let collider = world.write_resource::<CollisionHandler>().add_cuboid(
+        player,
+        &transform,
+        CollisionLayer::Player,
+    );
+    world.write::<Transform>().insert(player, transform);
+    world.write::<Collider>().insert(player, collider);
+
+    // Without collider, nothing stops the entity
+    let mut ghost_moving = Moving::new();
+    ghost_moving.velocity = Translation2::new(0., 20.);
+    let ghost = world
+        .create_entity()
+        .with(Transform::new(
+            Vector2::new(420., 536.),
+            Point2::new(32., 32.),
+        ))
+        .with(ghost_moving)
+        .build();
+
+    SysMoving {}.run_now(&world.res);
+
+    let transforms = world.read::<Transform>();
+    let moving = world.read::<Moving>();
+    assert_eq!(
+        transforms.get(player).unwrap().isometry.translation.vector,
+        Vector2::new(420., 544.)
+    );
+    assert_eq!(
+        moving.get(player).unwrap().velocity.vector,
+        Vector2::new(0., 0.)
+    );
+    assert_eq!(
+        transforms.get(ghost).unwrap().isometry.translation.vector,
+        Vector2::new(420., 556.)
+    );
+}
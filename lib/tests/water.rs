@@ -0,0 +1,272 @@
+extern crate some_platformer_lib as lib;
+
+mod common;
+
+use common::{
+    add_body, add_player, approx_eq, enter, new_world, position, set_velocity, velocity, X,
+};
+use lib::components::controller::CharacterController;
+use lib::components::input::Input;
+use lib::components::moving::Moving;
+use lib::components::rigid_body::RigidBody;
+use lib::components::water::{submerged, Water};
+use lib::entities::trigger::TriggerZone;
+use lib::map::{Map, WaterData};
+use lib::nalgebra::{Point2, Vector2};
+use lib::resources::gravity::Gravity;
+use lib::resources::trigger_events::{TriggerEvent, TriggerEvents};
+use lib::resources::water_events::{Splash, WaterEvent, WaterEvents};
+use lib::specs::{Entity, Join, RunNow};
+use lib::systems::sys_controller::SysController;
+use lib::systems::sys_water::SysWater;
+use lib::types::Rect;
+use lib::world::gameworld::GameWorld;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// The surface of the water added by `add_water`
+const SURFACE: f32 = -200.;
+
+#[test]
+fn entities_are_submerged_by_the_part_under_the_surface() {
+    let volume = Rect::new(0., 100., 200., 100.);
+
+    assert!(submerged(&Rect::new(50., 40., 32., 32.), &volume).abs() < 0.01);
+    assert!((submerged(&Rect::new(50., 84., 32., 32.), &volume) - 0.5).abs() < 0.01);
+    assert!((submerged(&Rect::new(50., 150., 32., 32.), &volume) - 1.).abs() < 0.01);
+}
+
+// A volume of water 400 pixels wide and deep, centered on (X, 0)
+fn add_water(world: &mut GameWorld, water: Water) -> Entity {
+    world
+        .add_game_entity(TriggerZone::water(
+            Point2::new(X, 0.),
+            Point2::new(400., 400.),
+            water,
+        ))
+        .entity()
+}
+
+fn force(world: &GameWorld, entity: Entity) -> Vector2<f32> {
+    world
+        .entity_world
+        .read::<Moving>()
+        .get(entity)
+        .unwrap()
+        .force
+}
+
+#[test]
+fn buoyancy_grows_with_the_depth() {
+    let mut world = GameWorld::new();
+    let water = add_water(&mut world, Water::new(2., 3.));
+    let deep = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    let half = add_body(&mut world, 100., SURFACE, RigidBody::new(1.));
+    enter(&mut world, water, deep);
+    enter(&mut world, water, half);
+
+    SysWater {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(force(&world, deep), Vector2::new(0., -3000.)));
+    assert!(approx_eq(force(&world, half), Vector2::new(0., -1500.)));
+}
+
+#[test]
+fn buoyancy_is_against_the_gravity_pulling_the_entities() {
+    let mut world = GameWorld::new();
+    *world.entity_world.write_resource::<Gravity>() = Gravity::new_custom(0., 1000.);
+    let water = add_water(&mut world, Water::new(2., 3.));
+    let body = add_body(&mut world, -100., 0., RigidBody::new(1.));
+    let player = add_player(&mut world, 100., 0.);
+    enter(&mut world, water, body);
+    enter(&mut world, water, player);
+
+    SysWater {}.run_now(&world.entity_world.res);
+
+    // The world's gravity pulls the body, the controller's own gravity (1500) the player
+    assert!(approx_eq(force(&world, body), Vector2::new(0., -2000.)));
+    assert!(approx_eq(force(&world, player), Vector2::new(0., -3000.)));
+}
+
+#[test]
+fn drag_slows_the_entities_down() {
+    let mut world = GameWorld::new();
+    let water = add_water(&mut world, Water::new(0., 3.));
+    let body = add_body(&mut world, 0., 0., RigidBody::new(1.));
+    set_velocity(&mut world, body, Vector2::new(100., -50.));
+    enter(&mut world, water, body);
+
+    SysWater {}.run_now(&world.entity_world.res);
+
+    assert!(approx_eq(force(&world, body), Vector2::new(-300., 150.)));
+}
+
+#[test]
+fn light_bodies_float_and_heavy_ones_sink() {
+    let mut world = GameWorld::new();
+    add_water(&mut world, Water::default());
+    let light = add_body(&mut world, -100., SURFACE + 10., RigidBody::new(1.));
+    let heavy = add_body(&mut world, 100., SURFACE + 10., RigidBody::new(3.));
+
+    // The water is entered once the collisions are detected, during the first update
+    for _ in 0..300 {
+        world.update_by(Duration::from_millis(16));
+    }
+
+    // Half submerged, as dense as the water
+    assert!((position(&world, light).y - SURFACE).abs() < 1.);
+    assert!(position(&world, heavy).y > SURFACE + 150.);
+}
+
+#[test]
+fn entering_and_exiting_the_water_splashes() {
+    let mut world = GameWorld::new();
+    let water = add_water(&mut world, Water::default());
+    let body = add_body(&mut world, 0., SURFACE, RigidBody::new(1.));
+    set_velocity(&mut world, body, Vector2::new(20., 300.));
+    enter(&mut world, water, body);
+
+    SysWater {}.run_now(&world.entity_world.res);
+
+    let splash = Splash {
+        water,
+        entity: body,
+        position: Vector2::new(X, SURFACE),
+        velocity: Vector2::new(20., 300.),
+    };
+    {
+        let events = world.entity_world.read_resource::<WaterEvents>();
+        assert_eq!(
+            events.iter().collect::<Vec<_>>(),
+            vec![&WaterEvent::Enter(splash)]
+        );
+    }
+
+    // Not while staying in it
+    {
+        let mut events = world.entity_world.write_resource::<TriggerEvents>();
+        events.clear();
+        events.push(TriggerEvent::Stay(water, body));
+    }
+    SysWater {}.run_now(&world.entity_world.res);
+    assert!(world.entity_world.read_resource::<WaterEvents>().is_empty());
+
+    {
+        let mut events = world.entity_world.write_resource::<TriggerEvents>();
+        events.clear();
+        events.push(TriggerEvent::Exit(water, body));
+    }
+    SysWater {}.run_now(&world.entity_world.res);
+    let events = world.entity_world.read_resource::<WaterEvents>();
+    assert_eq!(
+        events.iter().collect::<Vec<_>>(),
+        vec![&WaterEvent::Exit(splash)]
+    );
+}
+
+fn add_swimmer(world: &mut GameWorld, water: Entity, y: f32) -> Entity {
+    let player = add_player(world, 0., y);
+    enter(world, water, player);
+
+    player
+}
+
+fn swim(world: &mut GameWorld, player: Entity, input: Input) {
+    *world.entity_world.write::<Input>().get_mut(player).unwrap() = input;
+    SysController {}.run_now(&world.entity_world.res);
+}
+
+fn is_swimming(world: &GameWorld, player: Entity) -> bool {
+    world
+        .entity_world
+        .read::<CharacterController>()
+        .get(player)
+        .unwrap()
+        .is_swimming()
+}
+
+#[test]
+fn characters_swim_towards_the_direction_held() {
+    let mut world = new_world();
+    let water = add_water(&mut world, Water::default());
+    let player = add_swimmer(&mut world, water, 0.);
+    let controller = CharacterController::new();
+
+    let down_right = Input {
+        down: true,
+        right: true,
+        ..Input::new()
+    };
+    for _ in 0..30 {
+        swim(&mut world, player, down_right);
+    }
+
+    assert!(is_swimming(&world, player));
+    assert!(approx_eq(
+        velocity(&world, player),
+        Vector2::new(controller.swim_speed, controller.swim_speed)
+    ));
+
+    // A stroke upwards, under the water
+    let jump = Input {
+        jump: true,
+        ..Input::new()
+    };
+    swim(&mut world, player, jump);
+    assert!((velocity(&world, player).y + controller.swim_speed).abs() < 0.01);
+}
+
+#[test]
+fn characters_jump_out_at_the_surface() {
+    let mut world = new_world();
+    let water = add_water(&mut world, Water::default());
+    let player = add_swimmer(&mut world, water, SURFACE);
+
+    let jump = Input {
+        jump: true,
+        ..Input::new()
+    };
+    swim(&mut world, player, jump);
+
+    assert!(is_swimming(&world, player));
+    assert!((velocity(&world, player).y + CharacterController::new().jump_speed()).abs() < 0.01);
+}
+
+#[test]
+fn characters_wade_in_shallow_water() {
+    let mut world = new_world();
+    let water = add_water(&mut world, Water::default());
+    // A quarter submerged
+    let player = add_swimmer(&mut world, water, SURFACE - 8.);
+
+    swim(&mut world, player, Input::new());
+
+    assert!(!is_swimming(&world, player));
+}
+
+#[test]
+fn maps_spawn_their_water() {
+    let mut world = GameWorld::new();
+    let map = Map {
+        elements: HashMap::new(),
+        moving_platforms: Vec::new(),
+        triggers: Vec::new(),
+        effectors: Vec::new(),
+        water: vec![WaterData {
+            position: (0., 0.),
+            size: (100., 100.),
+            water: Water::default(),
+        }],
+    };
+    let count = |world: &GameWorld| {
+        let water = world.entity_world.read::<Water>();
+        (&water).join().count()
+    };
+
+    world.set_map(map.clone());
+    assert_eq!(count(&world), 1);
+
+    // Replacing the map replaces its water
+    world.set_map(map);
+    assert_eq!(count(&world), 1);
+}
@@ -0,0 +1,268 @@
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lib::sync::message::Server;
+use lib::tokio::io;
+use lib::tokio::net::TcpStream;
+use lib::tokio::prelude::*;
+use lib::tokio::util::FutureExt;
+use lib::Map;
+
+use game::GameCommand;
+use sync::state::StateHandle;
+use sync::CommandSender;
+
+const HELP: &str = "commands:
+  players                   list the connected players, with their RTT
+  kick <addr> [reason]      disconnect a player
+  ban <addr> [reason]       disconnect a player, and refuse its address
+  unban <ip>                allow a banned address again
+  map <path>                load a new map
+  pause                     stop updating the world
+  resume                    resume updating the world
+  step [frames]             update the world for some frames, while paused
+  tickrate <hz>             change the number of updates per second
+  say <message>             broadcast an announcement to all players
+  help                      show this help";
+
+/// How long a new connection has to send the `auth` line before being closed
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The admin console logic, shared by all the admin connections
+///
+/// The protocol is line based, human friendly (e.g. usable with `telnet`):
+/// the first line must be `auth <password>`, then each line is a command.
+pub struct Admin {
+    /// Handle to peer list
+    state: StateHandle,
+
+    /// Transmission half of the admin -> game channel
+    commands: Mutex<CommandSender>,
+
+    /// The password expected by `auth`
+    password: String,
+
+    /// Whether the game was paused (by an admin), `step` being allowed only then
+    paused: AtomicBool,
+}
+
+impl Admin {
+    pub fn new(state: StateHandle, commands: CommandSender, password: String) -> Self {
+        Admin {
+            state,
+            commands: Mutex::new(commands),
+            password,
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    /// Checks the authentication line
+    ///
+    /// The password is compared in constant time, not to leak how much of it is right.
+    fn authenticate(&self, line: &str) -> bool {
+        let mut words = line.splitn(2, ' ');
+        words.next() == Some("auth")
+            && words.next().map_or(false, |password| {
+                constant_time_eq(password.as_bytes(), self.password.as_bytes())
+            })
+    }
+
+    /// Runs a command, returns the response to the admin
+    pub fn execute(&self, line: &str) -> Result<String, String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or("");
+        let args: Vec<&str> = words.collect();
+
+        match (command, args.len()) {
+            ("players", 0) => Ok(self.players()),
+            ("kick", n) if n >= 1 => {
+                let addr = parse_addr(args[0])?;
+                self.kick(&addr, &args[1..].join(" "))
+            }
+            ("ban", n) if n >= 1 => {
+                let addr = parse_addr(args[0])?;
+                self.state.lock().unwrap().banned.insert(addr.ip());
+                // The player might already be gone, the ban still applies
+                let _ = self.kick(&addr, &args[1..].join(" "));
+                Ok(format!("{} banned", addr.ip()))
+            }
+            ("unban", 1) => {
+                let ip = args[0]
+                    .parse()
+                    .map_err(|_| format!("invalid ip {}", args[0]))?;
+                if self.state.lock().unwrap().banned.remove(&ip) {
+                    Ok(format!("{} unbanned", ip))
+                } else {
+                    Err(format!("{} is not banned", ip))
+                }
+            }
+            ("map", 1) => {
+                let map = Map::load(args[0])
+                    .map_err(|err| format!("cannot load {}: {}", args[0], err))?;
+                self.send(GameCommand::ChangeMap(map))
+            }
+            ("pause", 0) => self.set_paused(true),
+            ("resume", 0) => self.set_paused(false),
+            ("step", 0) => self.step(1),
+            ("step", 1) => {
+                let frames = args[0]
+                    .parse()
+                    .map_err(|_| format!("invalid frame count {}", args[0]))?;
+                self.step(frames)
+            }
+            ("tickrate", 1) => match args[0].parse() {
+                Ok(tick_rate) if tick_rate > 0 && tick_rate <= 1000 => {
+                    self.send(GameCommand::SetTickRate(tick_rate))
+                }
+                _ => Err(format!(
+                    "tick rate must be between 1 and 1000, got {}",
+                    args[0]
+                )),
+            },
+            ("say", n) if n >= 1 => {
+                self.state
+                    .lock()
+                    .unwrap()
+                    .broadcast(&Server::Announcement(args.join(" ")));
+                Ok("announced".into())
+            }
+            ("help", 0) => Ok(HELP.into()),
+            _ => Err(format!("invalid command {:?}, try `help`", line)),
+        }
+    }
+
+    /// Lists the players, one per line
+    fn players(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut players: Vec<String> = state
+            .peers
+            .iter()
+            .map(|(addr, peer)| {
+                format!(
+                    "{}\t{}\t{}",
+                    addr,
+                    peer.name.as_ref().map(String::as_str).unwrap_or("-"),
+                    peer.rtt.map(format_rtt).unwrap_or_else(|| "-".into()),
+                )
+            })
+            .collect();
+        players.sort();
+
+        format!("{} player(s)\n{}", players.len(), players.join("\n"))
+            .trim_end()
+            .to_string()
+    }
+
+    fn kick(&self, addr: &SocketAddr, reason: &str) -> Result<String, String> {
+        let reason = if reason.is_empty() {
+            "kicked by admin"
+        } else {
+            reason
+        };
+
+        if self.state.lock().unwrap().kick(addr, reason) {
+            Ok(format!("{} kicked", addr))
+        } else {
+            Err(format!("{} is not connected", addr))
+        }
+    }
+
+    fn set_paused(&self, paused: bool) -> Result<String, String> {
+        let command = if paused {
+            GameCommand::Pause
+        } else {
+            GameCommand::Resume
+        };
+        let response = self.send(command)?;
+        self.paused.store(paused, Ordering::SeqCst);
+
+        Ok(response)
+    }
+
+    fn step(&self, frames: u32) -> Result<String, String> {
+        if !self.paused.load(Ordering::SeqCst) {
+            return Err("the game is not paused, `pause` it first".into());
+        }
+
+        self.send(GameCommand::Step(frames))
+    }
+
+    fn send(&self, command: GameCommand) -> Result<String, String> {
+        self.commands
+            .lock()
+            .unwrap()
+            .send(command)
+            .map(|_| "ok".into())
+            .map_err(|_| "the game is stopped".into())
+    }
+}
+
+fn parse_addr(addr: &str) -> Result<SocketAddr, String> {
+    addr.parse()
+        .map_err(|_| format!("invalid address {}, expected ip:port", addr))
+}
+
+/// Compares two byte strings in a time depending only on their lengths
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn format_rtt(rtt: Duration) -> String {
+    let ms = rtt.as_secs() as f32 * 1000. + rtt.subsec_nanos() as f32 / 1_000_000.;
+    format!("{:0.2}ms", ms)
+}
+
+/// The task handling an admin connection
+pub type AdminSession = Box<Future<Item = (), Error = io::Error> + Send>;
+
+/// Builds the task handling an admin connection
+///
+/// The connection is closed if the authentication fails, or doesn't come in time.
+pub fn session(socket: TcpStream, addr: SocketAddr, admin: Arc<Admin>) -> AdminSession {
+    let (reader, writer) = socket.split();
+
+    // The first line must authenticate
+    let first_line = io::lines(BufReader::new(reader))
+        .into_future()
+        .map_err(|(err, _)| err)
+        .deadline(Instant::now() + AUTH_TIMEOUT)
+        .map_err(|err| {
+            err.into_inner().unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::TimedOut, "authentication timed out")
+            })
+        });
+
+    let session = first_line.and_then(move |(line, lines)| -> AdminSession {
+        if !line.map_or(false, |line| admin.authenticate(line.trim())) {
+            warn!("admin {} failed to authenticate", addr);
+            let failed = io::write_all(writer, "error: authentication failed\n").and_then(|_| {
+                Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "authentication failed",
+                ))
+            });
+            return Box::new(failed);
+        }
+        info!("admin {} authenticated", addr);
+
+        // Then each line is a command, answered by a response line
+        let commands = io::write_all(writer, "authenticated\n").and_then(move |(writer, _)| {
+            lines.fold(writer, move |writer, line| {
+                let line = line.trim();
+                info!("admin {} runs {:?}", addr, line);
+                let response = match admin.execute(line) {
+                    Ok(response) => response,
+                    Err(error) => format!("error: {}", error),
+                };
+
+                io::write_all(writer, response + "\n").map(|(writer, _)| writer)
+            })
+        });
+        Box::new(commands.map(|_| ()))
+    });
+
+    Box::new(session)
+}
@@ -0,0 +1,208 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use toml;
+
+/// The server configuration
+///
+/// Loaded from a TOML file, every missing field falls back to its default value.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Address the game server listens on
+    pub bind: SocketAddr,
+
+    /// Number of game updates per second
+    pub tick_rate: u32,
+
+    /// Maximum number of connected players, new connections are refused beyond
+    pub max_players: usize,
+
+    /// Path to the map file, the debug map is used when not set
+    pub map: Option<PathBuf>,
+
+    /// Log specification, as understood by `flexi_logger`
+    /// (can be overwritten by `RUST_LOG` env variable)
+    pub log: String,
+
+    /// Seconds without any message from a client before it is disconnected
+    pub client_timeout: u64,
+
+    /// Maximum number of messages per second accepted from a client
+    pub rate_limit: u32,
+
+    /// Seconds between two RTT measurements, the clients are not pinged when 0
+    pub ping_interval: u64,
+
+    /// Where to save the game state on shutdown, not saved when not set
+    pub save_path: Option<PathBuf>,
+
+    /// Address of the admin console, should stay local
+    pub admin_bind: SocketAddr,
+
+    /// Password of the admin console, the console is disabled when not set
+    pub admin_password: Option<String>,
+
+    /// Address of the HTTP metrics endpoint, disabled when not set
+    pub metrics_bind: Option<SocketAddr>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bind: "0.0.0.0:3000".parse().unwrap(),
+            tick_rate: 60,
+            max_players: 16,
+            map: None,
+            log: "some_platformer_lib=debug,some_platformer_server=debug".into(),
+            client_timeout: 30,
+            rate_limit: 100,
+            ping_interval: 1,
+            save_path: None,
+            admin_bind: "127.0.0.1:3001".parse().unwrap(),
+            admin_password: None,
+            metrics_bind: None,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration from a TOML file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+
+        let mut content = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut content))
+            .map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+
+        toml::from_str(&content).map_err(ConfigError::Parse)
+    }
+
+    /// Checks the configuration values are usable
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.tick_rate == 0 || self.tick_rate > 1000 {
+            return Err(ConfigError::Invalid(format!(
+                "tick_rate must be between 1 and 1000, got {}",
+                self.tick_rate
+            )));
+        }
+
+        if self.max_players == 0 {
+            return Err(ConfigError::Invalid(
+                "max_players must be at least 1".into(),
+            ));
+        }
+
+        if self.client_timeout == 0 {
+            return Err(ConfigError::Invalid(
+                "client_timeout must be at least 1 second".into(),
+            ));
+        }
+
+        if self.rate_limit == 0 {
+            return Err(ConfigError::Invalid(
+                "rate_limit must be at least 1 message per second".into(),
+            ));
+        }
+
+        if let Some(ref map) = self.map {
+            if !map.is_file() {
+                return Err(ConfigError::Invalid(format!(
+                    "map file {} does not exist",
+                    map.display()
+                )));
+            }
+        }
+
+        if let Some(ref password) = self.admin_password {
+            if password.is_empty() || password.contains(char::is_whitespace) {
+                return Err(ConfigError::Invalid(
+                    "admin_password must be non empty, without whitespaces".into(),
+                ));
+            }
+
+            if conflicts(self.admin_bind, self.bind) {
+                return Err(ConfigError::Invalid(
+                    "admin_bind must differ from bind".into(),
+                ));
+            }
+        }
+
+        if self
+            .metrics_bind
+            .map_or(false, |metrics_bind| conflicts(metrics_bind, self.bind))
+        {
+            return Err(ConfigError::Invalid(
+                "metrics_bind must differ from bind".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The duration of a game update
+    pub fn frame_budget(&self) -> Duration {
+        Duration::new(1, 0) / self.tick_rate
+    }
+
+    /// The duration of inactivity before disconnecting a client
+    pub fn client_timeout(&self) -> Duration {
+        Duration::from_secs(self.client_timeout)
+    }
+
+    /// The delay between two RTT measurements, if the clients are pinged
+    pub fn ping_interval(&self) -> Option<Duration> {
+        match self.ping_interval {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+}
+
+/// Whether two listeners can't be bound to both addresses
+///
+/// Port 0 lets the OS pick a free port, so it never conflicts.
+fn conflicts(a: SocketAddr, b: SocketAddr) -> bool {
+    a == b && a.port() != 0
+}
+
+/// An error preventing the server from starting with the given configuration
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The configuration file cannot be read
+    Io(PathBuf, io::Error),
+
+    /// The configuration file is not valid TOML, or has unexpected fields
+    Parse(toml::de::Error),
+
+    /// A value is out of its allowed range
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Io(ref path, ref err) => {
+                write!(f, "cannot read config file {}: {}", path.display(), err)
+            }
+            ConfigError::Parse(ref err) => write!(f, "invalid config file: {}", err),
+            ConfigError::Invalid(ref msg) => write!(f, "invalid config: {}", msg),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn description(&self) -> &str {
+        match *self {
+            ConfigError::Io(..) => "cannot read config file",
+            ConfigError::Parse(..) => "invalid config file",
+            ConfigError::Invalid(..) => "invalid config",
+        }
+    }
+}
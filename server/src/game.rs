@@ -1,10 +1,45 @@
+use metrics::Metrics;
 use sync::state::StateHandle;
-use sync::C2GReceiver;
+use sync::{C2GReceiver, CommandReceiver};
 
-use std::time::Duration;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
+use serde_json;
+
+use lib::components::transform::Transform;
+use lib::map::MapData;
+use lib::specs::Join;
 use lib::sync::message::{Client, Server};
 use lib::world::gameworld::GameWorld;
+use lib::Map;
+
+/// Delay between two replications of the moving platforms, in milliseconds
+///
+/// The clients move the platforms on their own in between.
+const PLATFORMS_SYNC_INTERVAL_MS: u64 = 100;
+
+/// An order given to the game, from the admin console
+#[derive(Debug)]
+pub enum GameCommand {
+    /// Replaces the current map
+    ChangeMap(Map),
+
+    /// Stops updating the world
+    Pause,
+
+    /// Resumes updating the world
+    Resume,
+
+    /// Updates the world for the given number of frames, while paused
+    Step(u32),
+
+    /// Changes the number of updates per second
+    SetTickRate(u32),
+}
 
 /// The game handle server logic:
 /// - Processing client messages
@@ -17,37 +52,185 @@ pub struct Game<'a, 'b> {
     // Receiver of the client -> server channel
     receiver: C2GReceiver,
 
+    // Receiver of the admin -> game channel
+    commands: CommandReceiver,
+
     // The world state
     world: GameWorld<'a, 'b>,
+
+    // The world is not updated while paused
+    paused: bool,
+
+    // Number of frames to update, even if paused
+    pending_steps: u32,
+
+    // The `maximum` duration for a frame
+    frame_budget: Duration,
+
+    // Delay between two RTT measurements, the peers are not pinged if `None`
+    ping_interval: Option<Duration>,
+
+    // Last time the peers were pinged
+    last_ping: Instant,
+
+    // Last time the moving platforms were sent to the peers
+    last_platforms_sync: Instant,
+
+    // The server metrics
+    metrics: Arc<Metrics>,
 }
 
 impl<'a, 'b> Game<'a, 'b> {
-    pub fn new(receiver: C2GReceiver, state: StateHandle) -> Self {
-        Game {
+    pub fn new(
+        receiver: C2GReceiver,
+        commands: CommandReceiver,
+        state: StateHandle,
+        map: Map,
+        frame_budget: Duration,
+        ping_interval: Option<Duration>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        let mut game = Game {
             state,
             receiver,
+            commands,
             world: GameWorld::new(),
-        }
+            paused: false,
+            pending_steps: 0,
+            frame_budget,
+            ping_interval,
+            last_ping: Instant::now(),
+            last_platforms_sync: Instant::now(),
+            metrics,
+        };
+        game.set_map(map);
+
+        game
+    }
+
+    /// Replaces the map, on the world and on the clients
+    fn set_map(&mut self, map: Map) {
+        self.state.lock().unwrap().set_map(MapData::from(&map));
+        self.world.set_map(map);
+    }
+
+    /// The `maximum` duration for a frame
+    pub fn frame_budget(&self) -> Duration {
+        self.frame_budget
     }
 
     /// Update the game state
     pub fn update(&mut self, _elapsed_time: Duration) {
+        // Apply admin commands
+        while let Ok(command) = self.commands.try_recv() {
+            info!("Game got a command: {:?}", command);
+
+            match command {
+                GameCommand::ChangeMap(map) => self.set_map(map),
+                GameCommand::Pause => self.paused = true,
+                GameCommand::Resume => {
+                    self.paused = false;
+                    self.pending_steps = 0;
+                }
+                // Stepping only makes sense while paused
+                GameCommand::Step(steps) => {
+                    if self.paused {
+                        self.pending_steps += steps;
+                    } else {
+                        warn!("not paused, ignoring {:?}", command);
+                    }
+                }
+                GameCommand::SetTickRate(tick_rate) => {
+                    self.frame_budget = Duration::new(1, 0) / tick_rate;
+                }
+            }
+        }
+
+        // Regularly ping the peers, to measure the RTT
+        let ping_due = self
+            .ping_interval
+            .map_or(false, |interval| self.last_ping.elapsed() >= interval);
+        if ping_due {
+            self.last_ping = Instant::now();
+            self.state
+                .lock()
+                .unwrap()
+                .broadcast(&Server::Ping(SystemTime::now()));
+        }
+
         // Poll messages from clients
         while let Ok((msg, author)) = self.receiver.try_recv() {
+            self.metrics.game_message_dequeued();
             debug!("Game got a message from {:?}: {:?}", author, msg);
 
             match msg {
                 // The `Test` message is dispatched to all peers but author
-                Client::Test => for (&addr, tx) in &self.state.lock().unwrap().peers {
+                Client::Test => for (&addr, peer) in &self.state.lock().unwrap().peers {
                     if addr != author {
-                        tx.unbounded_send(Server::Test).unwrap();
+                        peer.send(Server::Test);
                     }
                 },
-                Client::Ping(_) => unreachable!(), // the ping is handled by the peer
+                Client::Hello { name } => {
+                    info!("{} joined as {:?}", author, name);
+
+                    if let Some(peer) = self.state.lock().unwrap().peers.get_mut(&author) {
+                        peer.name = Some(name);
+                    }
+                }
+                // the ping and pong are handled by the peer
+                Client::Ping(_) | Client::Pong(_) => unreachable!(),
             }
         }
 
-        // Update the world state
-        self.world.update();
+        // Update the world state, unless paused
+        if self.paused {
+            if self.pending_steps == 0 {
+                return;
+            }
+            self.pending_steps -= 1;
+        }
+        self.world.update_by(self.frame_budget);
+
+        // Regularly replicate the moving platforms, correcting the clients' ones
+        let sync_interval = Duration::from_millis(PLATFORMS_SYNC_INTERVAL_MS);
+        if self.last_platforms_sync.elapsed() >= sync_interval {
+            self.last_platforms_sync = Instant::now();
+
+            let platforms = self.world.platform_states();
+            if !platforms.is_empty() {
+                self.state
+                    .lock()
+                    .unwrap()
+                    .broadcast(&Server::Platforms(platforms));
+            }
+        }
     }
+
+    /// Saves a snapshot of the world entities, as JSON
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let transforms = self.world.entity_world.read::<Transform>();
+        let entities: Vec<EntitySnapshot> = (&transforms)
+            .join()
+            .map(|tr| EntitySnapshot {
+                position: [
+                    tr.isometry.translation.vector.x,
+                    tr.isometry.translation.vector.y,
+                ],
+                rotation: tr.isometry.rotation.angle(),
+                size: [tr.size.x, tr.size.y],
+            })
+            .collect();
+
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(file, &entities)?;
+        Ok(())
+    }
+}
+
+/// The saved state of an entity
+#[derive(Serialize)]
+struct EntitySnapshot {
+    position: [f32; 2],
+    rotation: f32,
+    size: [f32; 2],
 }
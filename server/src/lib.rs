@@ -0,0 +1,451 @@
+extern crate some_platformer_lib as lib;
+
+#[macro_use]
+extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate toml;
+
+pub mod config;
+pub use config::Config;
+
+mod sync;
+use sync::peer::Peer;
+use sync::state::{State, StateHandle};
+use sync::{C2GSender, Codec};
+
+mod game;
+use game::Game;
+
+mod admin;
+use admin::Admin;
+
+mod metrics;
+use metrics::Metrics;
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Instant, SystemTime};
+
+use lib::futures::future::Shared;
+use lib::futures::sync::oneshot;
+use lib::sync::message::Server;
+use lib::tokio::net::{TcpListener, TcpStream};
+use lib::tokio::prelude::*;
+use lib::tokio::runtime::Runtime;
+use lib::Map;
+
+/// Resolves when the server is stopping, shared by all the tasks to stop
+type StopSignal = Shared<oneshot::Receiver<()>>;
+
+/// A handle to a running server
+///
+/// The server is stopped when the handle is shut down (or dropped).
+pub struct ServerHandle {
+    /// The address the server is actually bound to
+    addr: SocketAddr,
+
+    /// The address the admin console is actually bound to, if enabled
+    admin_addr: Option<SocketAddr>,
+
+    /// The address the metrics endpoint is actually bound to, if enabled
+    metrics_addr: Option<SocketAddr>,
+
+    /// The tokio runtime driving the listener and the peers
+    runtime: Option<Runtime>,
+
+    /// Handle to the connected peers
+    state: StateHandle,
+
+    /// Signals the listeners to stop accepting connections
+    stop_listeners: Option<oneshot::Sender<()>>,
+
+    /// Keeps the game loop running while `true`
+    running: Arc<AtomicBool>,
+
+    /// The dedicated game thread
+    game_thread: Option<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    /// The address the server is listening on
+    ///
+    /// Useful when binding to port 0, to know the port picked by the OS.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The address the admin console is listening on, if enabled
+    pub fn admin_addr(&self) -> Option<SocketAddr> {
+        self.admin_addr
+    }
+
+    /// The address the metrics endpoint is listening on, if enabled
+    pub fn metrics_addr(&self) -> Option<SocketAddr> {
+        self.metrics_addr
+    }
+
+    /// Gracefully stops the server
+    ///
+    /// Connected clients are notified with `reason` before being disconnected.
+    pub fn shutdown(mut self, reason: &str) {
+        self.stop(reason);
+    }
+
+    fn stop(&mut self, reason: &str) {
+        // The runtime is only taken once, when the server is stopped
+        let runtime = match self.runtime.take() {
+            Some(runtime) => runtime,
+            None => return,
+        };
+
+        info!("shutting down: {}", reason);
+
+        // Stop accepting new connections, and close the admin sessions
+        if let Some(stop_listeners) = self.stop_listeners.take() {
+            let _ = stop_listeners.send(());
+        }
+
+        // Stop the game loop, and wait for it to finish the current frame
+        // (and save its state, if configured to)
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(game_thread) = self.game_thread.take() {
+            if game_thread.join().is_err() {
+                error!("game thread panicked");
+            }
+        }
+
+        // Notify the clients, then drop the game -> client channels:
+        // each peer flushes its pending messages and disconnects.
+        {
+            let mut state = self.state.lock().unwrap();
+            state.broadcast(&Server::Shutdown {
+                reason: reason.to_string(),
+            });
+            state.peers.clear();
+        }
+
+        // Wait for all the peers to be flushed
+        let _ = runtime.shutdown_on_idle().wait();
+
+        info!("server stopped");
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stop("server stopped");
+    }
+}
+
+/// Starts a server with the given configuration
+///
+/// The game runs on a dedicated thread, and the network on a tokio runtime,
+/// both are owned by the returned handle.
+pub fn start(config: &Config) -> io::Result<ServerHandle> {
+    // The configuration might not come from the command line, which validates it
+    config
+        .validate()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    // Load the map before anything else, to fail early
+    let map = match config.map {
+        Some(ref path) => Map::load(path)?,
+        None => Map::default(),
+    };
+
+    // Open a TCP listener, the port might be 0 to let the OS pick one
+    let listener = TcpListener::bind(&config.bind)?;
+    let addr = listener.local_addr()?;
+
+    // The admin console, if enabled, is another listener
+    let admin_listener = match config.admin_password {
+        Some(_) => Some(TcpListener::bind(&config.admin_bind)?),
+        None => None,
+    };
+
+    // And so is the metrics endpoint
+    let metrics_listener = match config.metrics_bind {
+        Some(ref metrics_bind) => Some(TcpListener::bind(metrics_bind)?),
+        None => None,
+    };
+    let metrics = Arc::new(Metrics::new());
+
+    // Initialize the game state (the struct holding the connected players)
+    // and wrap it with Arc/Mutex, for thread sync.
+    let state = Arc::new(Mutex::new(State::new()));
+
+    // Create the peer -> game and admin -> game channels
+    let (sender, receiver) = mpsc::channel();
+    let (commands_sender, commands_receiver) = mpsc::channel();
+
+    // Make the game and spawn it to a new (dedicated) thread
+    let running = Arc::new(AtomicBool::new(true));
+    let game_state = state.clone();
+    let game_running = running.clone();
+    let game_metrics = metrics.clone();
+    let frame_budget = config.frame_budget();
+    let ping_interval = config.ping_interval();
+    let save_path = config.save_path.clone();
+    let game_thread = thread::Builder::new().name("game".into()).spawn(move || {
+        let mut game = Game::new(
+            receiver,
+            commands_receiver,
+            game_state,
+            map,
+            frame_budget,
+            ping_interval,
+            game_metrics.clone(),
+        );
+        game_loop(&mut game, &game_running, &game_metrics);
+
+        if let Some(path) = save_path {
+            match game.save(&path) {
+                Ok(()) => info!("game state saved to {}", path.display()),
+                Err(err) => error!("failed to save game state: {}", err),
+            }
+        }
+    })?;
+
+    // Setup server logic: on each new connection, we launch a new task
+    // handling communication with the client
+    let peer_config = config.clone();
+    let handle_state = state.clone();
+    let admin_state = state.clone();
+    let metrics_state = state.clone();
+    let peer_metrics = metrics.clone();
+    let server = listener
+        .incoming()
+        .for_each(move |socket| {
+            // A socket already disconnected has no address: drop it
+            let addr = match socket.peer_addr() {
+                Ok(addr) => addr,
+                Err(err) => {
+                    debug!("accepted socket already gone; err={:?}", err);
+                    return Ok(());
+                }
+            };
+            debug!("accepted socket; addr={:?}", addr);
+
+            // Refuse the connection (by dropping the socket) if the server is full,
+            // or the address is banned
+            {
+                let state = state.lock().unwrap();
+
+                if state.peers.len() >= peer_config.max_players {
+                    warn!("server full, refusing {:?}", addr);
+                    return Ok(());
+                }
+
+                if state.banned.contains(&addr.ip()) {
+                    warn!("{:?} is banned, refusing", addr);
+                    return Ok(());
+                }
+            }
+
+            spawn_peer(
+                socket,
+                addr,
+                state.clone(),
+                sender.clone(),
+                &peer_config,
+                peer_metrics.clone(),
+            );
+            Ok(())
+        })
+        .map_err(|err| {
+            // All task must return a `()` error type
+            // to force error handling
+            error!("accept error = {:?}", err);
+        });
+
+    // Stop the listeners as soon as the handle asks for it
+    let (stop_listeners, stop) = oneshot::channel();
+    let stop = stop.shared();
+
+    // Start the tokio runtime (reactor, threadpool, etc...)
+    // and spawn the `server` task onto it.
+    let mut runtime = Runtime::new()?;
+    runtime.spawn(until_stopped(server, &stop));
+
+    // Spawn the admin console task
+    let admin_addr = match (admin_listener, config.admin_password.clone()) {
+        (Some(admin_listener), Some(password)) => {
+            let admin = Admin::new(admin_state, commands_sender, password);
+            let admin_addr = admin_listener.local_addr()?;
+
+            runtime.spawn(until_stopped(
+                admin_listener_task(admin_listener, admin, stop.clone()),
+                &stop,
+            ));
+
+            info!("admin console running on {}", admin_addr);
+            Some(admin_addr)
+        }
+        _ => None,
+    };
+
+    // Spawn the metrics endpoint task
+    let metrics_addr = match metrics_listener {
+        Some(metrics_listener) => {
+            let metrics_addr = metrics_listener.local_addr()?;
+
+            runtime.spawn(until_stopped(
+                metrics_listener_task(metrics_listener, metrics, metrics_state),
+                &stop,
+            ));
+
+            info!("metrics served on http://{}/metrics", metrics_addr);
+            Some(metrics_addr)
+        }
+        None => None,
+    };
+
+    info!("server running on {}", addr);
+
+    Ok(ServerHandle {
+        addr,
+        admin_addr,
+        metrics_addr,
+        runtime: Some(runtime),
+        state: handle_state,
+        stop_listeners: Some(stop_listeners),
+        running,
+        game_thread: Some(game_thread),
+    })
+}
+
+/// Stops `task` as soon as the server is stopping
+fn until_stopped<F>(task: F, stop: &StopSignal) -> Box<Future<Item = (), Error = ()> + Send>
+where
+    F: Future<Item = (), Error = ()> + Send + 'static,
+{
+    let stop = stop.clone().map(|_| ()).map_err(|_| ());
+    Box::new(task.select(stop).map(|_| ()).map_err(|_| ()))
+}
+
+/// Builds the task accepting admin connections
+fn admin_listener_task(
+    listener: TcpListener,
+    admin: Admin,
+    stop: StopSignal,
+) -> Box<Future<Item = (), Error = ()> + Send> {
+    let admin = Arc::new(admin);
+
+    let task = listener
+        .incoming()
+        .for_each(move |socket| {
+            // A socket already disconnected has no address: drop it
+            let addr = match socket.peer_addr() {
+                Ok(addr) => addr,
+                Err(err) => {
+                    debug!("accepted admin socket already gone; err={:?}", err);
+                    return Ok(());
+                }
+            };
+            debug!("accepted admin socket; addr={:?}", addr);
+
+            let session = admin::session(socket, addr, admin.clone()).map_err(|err| {
+                warn!("admin session error = {:?}", err);
+            });
+            lib::tokio::spawn(until_stopped(session, &stop));
+            Ok(())
+        })
+        .map_err(|err| {
+            error!("admin accept error = {:?}", err);
+        });
+
+    Box::new(task)
+}
+
+/// Builds the task serving the metrics over HTTP
+fn metrics_listener_task(
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+    state: StateHandle,
+) -> Box<Future<Item = (), Error = ()> + Send> {
+    let task = listener
+        .incoming()
+        .for_each(move |socket| {
+            let request = metrics::serve(socket, metrics.clone(), state.clone()).map_err(|err| {
+                warn!("metrics request error = {:?}", err);
+            });
+            lib::tokio::spawn(request);
+            Ok(())
+        })
+        .map_err(|err| {
+            error!("metrics accept error = {:?}", err);
+        });
+
+    Box::new(task)
+}
+
+/// Builds a new task for the incoming stream
+/// the task will live until client disconnect
+/// and will handle/forward client messages
+fn spawn_peer(
+    socket: TcpStream,
+    addr: SocketAddr,
+    state: StateHandle,
+    sender: C2GSender,
+    config: &Config,
+    metrics: Arc<Metrics>,
+) {
+    // Wrap the socket with the `Lines` codec
+    // which will encode/decode message for and from the client
+    let lines = Codec::new(socket);
+
+    // Create the peer to manage the client logic
+    let peer = Peer::new(state, sender, lines, addr, config, metrics.clone()).map_err(move |err| {
+        // The `Lines` codec reports undecodable lines as invalid data
+        if err.kind() == io::ErrorKind::InvalidData {
+            metrics.record_decode_error();
+        }
+
+        error!("peer error = {:?}", err);
+    });
+
+    // Spawn the task on tokio executor
+    lib::tokio::spawn(peer);
+}
+
+/// Run the game loop, until `running` is set to `false`
+/// TODO: move to fixed time update
+fn game_loop(game: &mut Game, running: &AtomicBool, metrics: &Metrics) {
+    // Start time of the last frame
+    let mut last_frame_start = SystemTime::now();
+
+    // End time of the last frame
+    let mut last_frame_end = last_frame_start;
+
+    while running.load(Ordering::SeqCst) {
+        // Compute current frame start & end (based on last frame)
+        let frame_start = SystemTime::now();
+        let frame_budget = game.frame_budget();
+        let frame_end = last_frame_end + frame_budget;
+
+        let update_start = Instant::now();
+        game.update(
+            frame_start
+                .duration_since(last_frame_start)
+                .unwrap_or_default(),
+        );
+        metrics.record_tick(update_start.elapsed(), frame_budget);
+
+        // Update last frame infos
+        last_frame_end = frame_end;
+        last_frame_start = frame_start;
+
+        // Sleep until the computed frame end
+        let sleep_time = frame_end
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        thread::sleep(sleep_time);
+    }
+}
@@ -1,126 +1,146 @@
-extern crate some_platformer_lib as lib;
+extern crate some_platformer_server as server;
 
-extern crate flexi_logger;
 #[macro_use]
-extern crate log;
-
-mod sync;
-use sync::peer::Peer;
-use sync::state::{State, StateHandle};
-use sync::{C2GSender, Codec};
-
-mod game;
-use game::Game;
+extern crate clap;
+extern crate ctrlc;
+extern crate flexi_logger;
 
+use std::process;
+use std::str::FromStr;
 use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::{Duration, SystemTime};
-
-use lib::tokio::net::{TcpListener, TcpStream};
-use lib::tokio::prelude::*;
 
+use clap::{App, Arg, ArgMatches};
 use flexi_logger::Logger;
 
+use server::config::{Config, ConfigError};
+
 fn main() {
-    // Setup logger to debug by default (can be overwritten by `RUST_LOG` env variable
-    Logger::with_env_or_str("some_platformer_lib=debug,some_platformer_server=debug")
+    let matches = App::new("some_platformer_server")
+        .version(crate_version!())
+        .about("Dedicated server for some_platformer")
+        .arg(
+            Arg::with_name("config")
+                .short("c")
+                .long("config")
+                .value_name("FILE")
+                .help("TOML configuration file, overridden by the other options"),
+        )
+        .arg(
+            Arg::with_name("bind")
+                .long("bind")
+                .value_name("ADDR")
+                .help("Address to listen on (e.g. 0.0.0.0:3000)"),
+        )
+        .arg(
+            Arg::with_name("tick_rate")
+                .long("tick-rate")
+                .value_name("HZ")
+                .help("Number of game updates per second"),
+        )
+        .arg(
+            Arg::with_name("max_players")
+                .long("max-players")
+                .value_name("N")
+                .help("Maximum number of connected players"),
+        )
+        .arg(
+            Arg::with_name("map")
+                .long("map")
+                .value_name("FILE")
+                .help("Map file to load"),
+        )
+        .arg(
+            Arg::with_name("log")
+                .long("log")
+                .value_name("SPEC")
+                .help("Log specification (e.g. some_platformer_server=info)"),
+        )
+        .arg(
+            Arg::with_name("client_timeout")
+                .long("client-timeout")
+                .value_name("SECS")
+                .help("Disconnects clients silent for this long"),
+        )
+        .arg(
+            Arg::with_name("rate_limit")
+                .long("rate-limit")
+                .value_name("N")
+                .help("Maximum number of messages per second from a client"),
+        )
+        .get_matches();
+
+    let config = load_config(&matches).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    // Setup logger (can be overwritten by `RUST_LOG` env variable
+    Logger::with_env_or_str(&config.log)
         .start()
         .expect("Logger initialization failed");
 
-    // Initialize the game state (the struct holding the connected players)
-    // and wrap it with Arc/Mutex, for thread sync.
-    let state = Arc::new(Mutex::new(State::new()));
-
-    // Create the peer -> game channel
-    let (sender, receiver) = mpsc::channel();
-
-    // Make the game and spawn it to a new (dedicated) thread
-    let game_state = state.clone();
-    thread::spawn(|| game_loop(Game::new(receiver, game_state)));
-
-    // Open a TCP listener on port 3000, allowing all connections
-    let addr = "0.0.0.0:3000".parse().expect("invalid addr");
-    let listener = TcpListener::bind(&addr).expect("failed to bind port, maybe try another ?");
-
-    // Setup server logic: on each new connection, we launch a new task
-    // handling communication with the client
-    let server = listener
-        .incoming()
-        .for_each(move |socket| {
-            debug!("accepted socket; addr={:?}", socket.peer_addr().unwrap());
-
-            spawn_peer(socket, state.clone(), sender.clone());
-            Ok(())
-        })
-        .map_err(|err| {
-            // All task must return a `()` error type
-            // to force error handling
-            error!("accept error = {:?}", err);
-        });
-
-    info!("server running on port 3000");
-
-    // Start the server
-    //
-    // This does a few things:
-    //
-    // * Start the Tokio runtime (reactor, threadpool, etc...)
-    // * Spawns the `server` task onto the runtime.
-    // * Blocks the current thread until the runtime becomes idle, i.e.
-    //   spawned tasks have completed
-    lib::tokio::run(server);
-}
+    // Catch SIGINT / SIGTERM before starting, not to miss one sent while starting
+    let (stop_sender, stop_receiver) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = stop_sender.send(());
+    })
+    .expect("failed to set signal handler");
 
-/// Builds a new task for the incoming stream
-/// the task will live until client disconnect
-/// and will handle/forward client messages
-fn spawn_peer(socket: TcpStream, state: StateHandle, sender: C2GSender) {
-    // Wrap the socket with the `Lines` codec
-    // which will encode/decode message for and from the client
-    let lines = Codec::new(socket);
-
-    // Create the peer to manage the client logic
-    let peer = Peer::new(state, sender, lines).map_err(|err| {
-        error!("peer error = {:?}", err);
-        ()
+    let server = server::start(&config).unwrap_or_else(|err| {
+        eprintln!("failed to start server on {}: {}", config.bind, err);
+        process::exit(1);
     });
 
-    // Spawn the task on tokio executor
-    lib::tokio::spawn(peer);
+    // Wait for SIGINT / SIGTERM
+    let _ = stop_receiver.recv();
+
+    server.shutdown("server is shutting down");
+}
+
+/// Builds the configuration from the config file and the command line options
+fn load_config(matches: &ArgMatches) -> Result<Config, ConfigError> {
+    let mut config = match matches.value_of("config") {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+
+    if let Some(bind) = parse_arg(matches, "bind")? {
+        config.bind = bind;
+    }
+    if let Some(tick_rate) = parse_arg(matches, "tick_rate")? {
+        config.tick_rate = tick_rate;
+    }
+    if let Some(max_players) = parse_arg(matches, "max_players")? {
+        config.max_players = max_players;
+    }
+    if let Some(map) = matches.value_of("map") {
+        config.map = Some(map.into());
+    }
+    if let Some(log) = matches.value_of("log") {
+        config.log = log.into();
+    }
+    if let Some(client_timeout) = parse_arg(matches, "client_timeout")? {
+        config.client_timeout = client_timeout;
+    }
+    if let Some(rate_limit) = parse_arg(matches, "rate_limit")? {
+        config.rate_limit = rate_limit;
+    }
+
+    config.validate()?;
+
+    Ok(config)
 }
 
-/// Run the game loop
-/// TODO: move to fixed time update
-fn game_loop(mut game: Game) {
-    // The `maximum` duration for a frame
-    let frame_budget = Duration::new(1, 0) / 60;
-
-    // Start time of the last frame
-    let mut last_frame_start = SystemTime::now();
-
-    // End time of the last frame
-    let mut last_frame_end = last_frame_start;
-
-    loop {
-        // Compute current frame start & end (based on last frame)
-        let frame_start = SystemTime::now();
-        let frame_end = last_frame_end + frame_budget;
-
-        game.update(
-            frame_start
-                .duration_since(last_frame_start)
-                .unwrap_or_default(),
-        );
-
-        // Update last frame infos
-        last_frame_end = frame_end;
-        last_frame_start = frame_start;
-
-        // Sleep until the computed frame end
-        let sleep_time = frame_end
-            .duration_since(SystemTime::now())
-            .unwrap_or_default();
-        thread::sleep(sleep_time);
+/// Parses an optional command line value
+fn parse_arg<T: FromStr>(matches: &ArgMatches, name: &str) -> Result<Option<T>, ConfigError> {
+    match matches.value_of(name) {
+        Some(value) => value.parse().map(Some).map_err(|_| {
+            ConfigError::Invalid(format!(
+                "invalid value for --{}: {}",
+                name.replace('_', "-"),
+                value
+            ))
+        }),
+        None => Ok(None),
     }
 }
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io::BufReader;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lib::tokio::io;
+use lib::tokio::net::TcpStream;
+use lib::tokio::prelude::*;
+use lib::tokio::util::FutureExt;
+
+use sync::state::{State, StateHandle};
+
+/// Upper bounds of the tick duration histogram buckets, in seconds
+const TICK_BUCKETS: &[f64] = &[0.001, 0.002, 0.004, 0.008, 0.016, 0.032, 0.064, 0.128];
+
+/// How long a client has to send its request line before being disconnected
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Direction of a message, relative to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    In,
+    Out,
+}
+
+impl Direction {
+    fn label(self) -> &'static str {
+        match self {
+            Direction::In => "in",
+            Direction::Out => "out",
+        }
+    }
+}
+
+/// Number of messages, and their total size
+#[derive(Default)]
+struct Traffic {
+    messages: u64,
+    bytes: u64,
+}
+
+/// A histogram with fixed buckets
+struct Histogram {
+    /// Number of observations per bucket (not cumulative), the last one being `+Inf`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Histogram {
+            buckets: vec![0; TICK_BUCKETS.len() + 1],
+            sum: 0.,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = TICK_BUCKETS
+            .iter()
+            .position(|&bound| value <= bound)
+            .unwrap_or_else(|| TICK_BUCKETS.len());
+
+        self.buckets[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// The server metrics, shared by the game and the peers
+///
+/// The connected peers and outgoing queues are read from the `State`
+/// when rendering.
+pub struct Metrics {
+    /// Duration of the game updates
+    tick_duration: Mutex<Histogram>,
+
+    /// Number of game updates that exceeded their frame budget
+    tick_overruns: AtomicUsize,
+
+    /// Messages and bytes, per direction and message kind
+    traffic: Mutex<BTreeMap<(Direction, &'static str), Traffic>>,
+
+    /// Number of lines the `Lines` codec failed to decode
+    decode_errors: AtomicUsize,
+
+    /// Number of client messages waiting for the game
+    game_queue_depth: AtomicUsize,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            tick_duration: Mutex::new(Histogram::new()),
+            tick_overruns: AtomicUsize::new(0),
+            traffic: Mutex::new(BTreeMap::new()),
+            decode_errors: AtomicUsize::new(0),
+            game_queue_depth: AtomicUsize::new(0),
+        }
+    }
+
+    /// Records the duration of a game update
+    pub fn record_tick(&self, duration: Duration, frame_budget: Duration) {
+        self.tick_duration
+            .lock()
+            .unwrap()
+            .observe(duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) * 1e-9);
+
+        if duration > frame_budget {
+            self.tick_overruns.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a message sent or received by a peer
+    pub fn record_message(&self, direction: Direction, kind: &'static str, bytes: usize) {
+        let mut all = self.traffic.lock().unwrap();
+        let traffic = all
+            .entry((direction, kind))
+            .or_insert_with(Traffic::default);
+        traffic.messages += 1;
+        traffic.bytes += bytes as u64;
+    }
+
+    /// Records a line the codec failed to decode
+    pub fn record_decode_error(&self) {
+        self.decode_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A client message was queued for the game
+    pub fn game_message_queued(&self) {
+        self.game_queue_depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The game took a client message from its queue
+    pub fn game_message_dequeued(&self) {
+        self.game_queue_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Renders the metrics in the Prometheus text format
+    pub fn render(&self, state: &State) -> String {
+        let mut out = String::new();
+
+        gauge(
+            &mut out,
+            "some_platformer_connected_peers",
+            "Number of connected clients",
+            state.peers.len(),
+        );
+
+        let outgoing: usize = state.peers.values().map(|peer| peer.queue_depth()).sum();
+        gauge(
+            &mut out,
+            "some_platformer_outgoing_queue_depth",
+            "Number of messages waiting to be sent to the clients",
+            outgoing,
+        );
+
+        gauge(
+            &mut out,
+            "some_platformer_game_queue_depth",
+            "Number of client messages waiting for the game",
+            self.game_queue_depth.load(Ordering::Relaxed),
+        );
+
+        {
+            let histogram = self.tick_duration.lock().unwrap();
+            let name = "some_platformer_tick_duration_seconds";
+            header(&mut out, name, "Duration of the game updates", "histogram");
+
+            let mut cumulative = 0;
+            for (bound, count) in TICK_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+            }
+            let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, histogram.count);
+            let _ = writeln!(out, "{}_sum {}", name, histogram.sum);
+            let _ = writeln!(out, "{}_count {}", name, histogram.count);
+        }
+
+        counter(
+            &mut out,
+            "some_platformer_tick_overruns_total",
+            "Number of game updates that exceeded their frame budget",
+            self.tick_overruns.load(Ordering::Relaxed),
+        );
+
+        counter(
+            &mut out,
+            "some_platformer_decode_errors_total",
+            "Number of client lines that could not be decoded",
+            self.decode_errors.load(Ordering::Relaxed),
+        );
+
+        {
+            let traffic = self.traffic.lock().unwrap();
+
+            let name = "some_platformer_messages_total";
+            header(&mut out, name, "Number of messages, per kind", "counter");
+            for (&(direction, kind), traffic) in traffic.iter() {
+                let _ = writeln!(
+                    out,
+                    "{}{{direction=\"{}\",kind=\"{}\"}} {}",
+                    name,
+                    direction.label(),
+                    kind,
+                    traffic.messages
+                );
+            }
+
+            let name = "some_platformer_message_bytes_total";
+            header(
+                &mut out,
+                name,
+                "Size of the messages in bytes, per kind",
+                "counter",
+            );
+            for (&(direction, kind), traffic) in traffic.iter() {
+                let _ = writeln!(
+                    out,
+                    "{}{{direction=\"{}\",kind=\"{}\"}} {}",
+                    name,
+                    direction.label(),
+                    kind,
+                    traffic.bytes
+                );
+            }
+        }
+
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: usize) {
+    header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: usize) {
+    header(out, name, help, "counter");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// The task answering a metrics HTTP request
+pub type MetricsRequest = Box<Future<Item = (), Error = io::Error> + Send>;
+
+/// Builds the task answering a HTTP request on `socket`
+///
+/// Only `GET /metrics` is served, the connection is closed after the response (or if the
+/// request doesn't come in time).
+pub fn serve(socket: TcpStream, metrics: Arc<Metrics>, state: StateHandle) -> MetricsRequest {
+    let request_line = io::read_until(BufReader::new(socket), b'\n', Vec::new())
+        .deadline(Instant::now() + REQUEST_TIMEOUT)
+        .map_err(|err| {
+            err.into_inner()
+                .unwrap_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "no request in time"))
+        });
+
+    let request = request_line.and_then(move |(reader, request_line)| {
+        // The metrics are rendered once the request came, to be up to date
+        let response = if request_line.starts_with(b"GET /metrics ") {
+            let body = metrics.render(&state.lock().unwrap());
+            format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        } else {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        };
+
+        io::write_all(reader.into_inner(), response)
+    });
+
+    Box::new(request.map(|_| ()))
+}
@@ -1,4 +1,5 @@
 pub mod peer;
+pub mod rate_limit;
 pub mod state;
 
 use std::net::SocketAddr;
@@ -9,6 +10,8 @@ use lib::sync::message::{Client, Server};
 
 use lib::futures::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
+use game::GameCommand;
+
 // SHORTHANDS
 /// game -> client channel
 pub type G2CSender = UnboundedSender<Server>;
@@ -18,5 +21,9 @@ pub type G2CReceiver = UnboundedReceiver<Server>;
 pub type C2GSender = Sender<(Client, SocketAddr)>;
 pub type C2GReceiver = Receiver<(Client, SocketAddr)>;
 
+// admin -> game channel
+pub type CommandSender = Sender<GameCommand>;
+pub type CommandReceiver = Receiver<GameCommand>;
+
 // server `Lines` codec
 pub type Codec = Lines<Server, Client>;
@@ -1,15 +1,21 @@
 use lib::sync::message::{Client, Server};
 
 use std::net::SocketAddr;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use lib::futures::sync::mpsc;
 
 use lib::tokio::io;
 use lib::tokio::prelude::*;
+use lib::tokio::timer::Delay;
 
-use super::state::StateHandle;
+use super::rate_limit::RateLimiter;
+use super::state::{PeerInfo, StateHandle};
 use super::{C2GSender, Codec, G2CReceiver};
+use config::Config;
+use metrics::{Direction, Metrics};
 
 /// A future that processes the broadcast logic for a connection
 pub struct Peer {
@@ -34,18 +40,44 @@ pub struct Peer {
     /// address is saved so that the `Peer` drop implementation can clean up its
     /// entry.
     addr: SocketAddr,
+
+    /// Disconnects the client when no message is received in time
+    timeout: Delay,
+
+    /// Duration of inactivity before disconnecting the client
+    timeout_duration: Duration,
+
+    /// Limits the number of messages accepted from the client
+    rate_limiter: RateLimiter,
+
+    /// Number of messages queued in `rx`, shared with the `PeerInfo`
+    queued: Arc<AtomicUsize>,
+
+    /// The server metrics
+    metrics: Arc<Metrics>,
 }
 
 impl Peer {
-    pub fn new(state: StateHandle, game: C2GSender, lines: Codec) -> Self {
-        // Get the client socket address
-        let addr = lines.peer_addr().unwrap();
-
+    pub fn new(
+        state: StateHandle,
+        game: C2GSender,
+        lines: Codec,
+        addr: SocketAddr,
+        config: &Config,
+        metrics: Arc<Metrics>,
+    ) -> Self {
         // Create a channel for this peer
         let (tx, rx) = mpsc::unbounded();
+        let queued = Arc::new(AtomicUsize::new(0));
 
-        // Add an entry for this `Peer` in the shared state map.
-        state.lock().unwrap().peers.insert(addr, tx);
+        // Add an entry for this `Peer` in the shared state map,
+        // the map played is sent first
+        state
+            .lock()
+            .unwrap()
+            .add_peer(addr, PeerInfo::new(tx, queued.clone()));
+
+        let timeout_duration = config.client_timeout();
 
         Peer {
             lines,
@@ -53,6 +85,11 @@ impl Peer {
             game,
             rx,
             addr,
+            timeout: Delay::new(Instant::now() + timeout_duration),
+            timeout_duration,
+            rate_limiter: RateLimiter::new(config.rate_limit),
+            queued,
+            metrics,
         }
     }
 }
@@ -72,32 +109,88 @@ impl Future for Peer {
 
         // Polling an `UnboundedReceiver` cannot fail, so `unwrap`
         // here is safe.
-        while let Async::Ready(Some(v)) = self.rx.poll().unwrap() {
-            // Buffer the line. Once all lines are buffered,
-            // they will be flushed to the socket (right
-            // below).
-            self.lines.buffer(&v)?;
+        let mut closed = false;
+        loop {
+            match self.rx.poll().unwrap() {
+                Async::Ready(Some(v)) => {
+                    self.queued.fetch_sub(1, Ordering::Relaxed);
+
+                    // Buffer the line. Once all lines are buffered,
+                    // they will be flushed to the socket (right
+                    // below).
+                    let bytes = self.lines.buffer(&v)?;
+                    self.metrics.record_message(Direction::Out, v.kind(), bytes);
+                }
+                // The game dropped our channel (shutdown or kick)
+                Async::Ready(None) => {
+                    closed = true;
+                    break;
+                }
+                Async::NotReady => break,
+            }
         }
 
         // Flush the write buffer to the socket
-        let _ = self.lines.poll_flush()?;
+        let flushed = self.lines.poll_flush()?.is_ready();
+
+        // Once everything is flushed, disconnect the client
+        if closed && flushed {
+            return Ok(Async::Ready(()));
+        }
 
         // Read new lines from the socket
         while let Async::Ready(line) = self.lines.poll()? {
             debug!("Received line {:?}", line);
 
             if let Some(message) = line {
-                if let Client::Ping(t) = message {
-                    let response = Server::Pong {
-                        client: t,
-                        server: SystemTime::now(),
-                    };
-
-                    self.state.lock().unwrap().peers[&self.addr]
-                        .unbounded_send(response)
-                        .unwrap();
-                } else {
-                    self.game.send((message, self.addr)).unwrap();
+                self.metrics.record_message(
+                    Direction::In,
+                    message.kind(),
+                    self.lines.last_line_len(),
+                );
+
+                // The client is alive, push back the timeout
+                self.timeout.reset(Instant::now() + self.timeout_duration);
+
+                if !self.rate_limiter.try_acquire() {
+                    warn!(
+                        "rate limit exceeded by {}, dropping {:?}",
+                        self.addr, message
+                    );
+                    continue;
+                }
+
+                match message {
+                    Client::Ping(t) => {
+                        let response = Server::Pong {
+                            client: t,
+                            server: SystemTime::now(),
+                        };
+
+                        if let Some(peer) = self.state.lock().unwrap().peers.get(&self.addr) {
+                            peer.send(response);
+                        }
+                    }
+                    Client::Pong(t) => {
+                        // Answer to our own ping, the RTT is the time elapsed since then
+                        let rtt = SystemTime::now().duration_since(t).unwrap_or_default();
+
+                        if let Some(peer) = self.state.lock().unwrap().peers.get_mut(&self.addr) {
+                            peer.rtt = Some(rtt);
+                        }
+                    }
+                    message => {
+                        // Counted before being sent, as the game might take it right away
+                        self.metrics.game_message_queued();
+
+                        // The game stopped (the server is shutting down), nobody
+                        // will handle the client anymore
+                        if self.game.send((message, self.addr)).is_err() {
+                            self.metrics.game_message_dequeued();
+                            info!("game stopped, closing {}", self.addr);
+                            return Ok(Async::Ready(()));
+                        }
+                    }
                 }
             } else {
                 // EOF was reached. The remote client has disconnected.
@@ -106,11 +199,22 @@ impl Future for Peer {
             }
         }
 
+        // Disconnect the client if it stayed silent for too long
+        let timed_out = self
+            .timeout
+            .poll()
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+            .is_ready();
+        if timed_out {
+            info!("client {} timed out", self.addr);
+            return Ok(Async::Ready(()));
+        }
+
         // As always, it is important to not just return `NotReady`
         // without ensuring an inner future also returned `NotReady`.
         //
-        // We know we got a `NotReady` from either `self.rx` or
-        // `self.lines`, so the contract is respected.
+        // We know we got a `NotReady` from `self.rx`, `self.lines`
+        // and `self.timeout`, so the contract is respected.
         Ok(Async::NotReady)
     }
 }
@@ -0,0 +1,40 @@
+use std::time::Instant;
+
+/// A token bucket limiting the number of messages accepted per second
+pub struct RateLimiter {
+    /// Number of messages allowed per second (and maximum burst)
+    rate: f32,
+
+    /// Currently available tokens
+    tokens: f32,
+
+    /// Last time the bucket was refilled
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate: u32) -> Self {
+        RateLimiter {
+            rate: rate as f32,
+            tokens: rate as f32,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Consumes a token, returns `false` if the limit is exceeded
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill);
+        let elapsed = elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9;
+
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            true
+        } else {
+            false
+        }
+    }
+}
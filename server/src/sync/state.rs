@@ -1,21 +1,113 @@
-use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use lib::map::MapData;
+use lib::sync::message::Server;
 
 use super::G2CSender;
 
 /// Shorthand for the shared handle to the state
 pub type StateHandle = Arc<Mutex<State>>;
 
+/// A connected client
+pub struct PeerInfo {
+    /// Transmission half of the game -> client channel
+    tx: G2CSender,
+
+    /// Name of the player, known once the client said `Hello`
+    pub name: Option<String>,
+
+    /// Last measured round trip time, known once the client answered a `Ping`
+    pub rtt: Option<Duration>,
+
+    /// Number of messages sent through `tx` not yet taken by the peer
+    queued: Arc<AtomicUsize>,
+}
+
+impl PeerInfo {
+    pub fn new(tx: G2CSender, queued: Arc<AtomicUsize>) -> Self {
+        PeerInfo {
+            tx,
+            name: None,
+            rtt: None,
+            queued,
+        }
+    }
+
+    /// Queues a message for the peer, returns `false` if the peer is disconnecting
+    pub fn send(&self, message: Server) -> bool {
+        // Counted before being sent, as the peer might take it (and uncount it) right away
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        let sent = self.tx.unbounded_send(message).is_ok();
+        if !sent {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+        }
+        sent
+    }
+
+    /// Number of messages waiting to be sent to the peer
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
 /// The shared state, to allow task to communicate together
 pub struct State {
-    pub peers: HashMap<SocketAddr, G2CSender>,
+    pub peers: HashMap<SocketAddr, PeerInfo>,
+
+    /// Addresses not allowed to connect
+    pub banned: HashSet<IpAddr>,
+
+    /// The map played, sent to the peers when they connect
+    pub map: Option<MapData>,
 }
 
 impl State {
     pub fn new() -> Self {
         State {
             peers: HashMap::new(),
+            banned: HashSet::new(),
+            map: None,
+        }
+    }
+
+    /// Sends a message to all the connected peers
+    pub fn broadcast(&self, message: &Server) {
+        for peer in self.peers.values() {
+            // The peer might be disconnecting, nothing to do then
+            peer.send(message.clone());
+        }
+    }
+
+    /// Registers a newly connected peer, sending it the current map
+    pub fn add_peer(&mut self, addr: SocketAddr, peer: PeerInfo) {
+        if let Some(ref map) = self.map {
+            peer.send(Server::Map(map.clone()));
+        }
+        self.peers.insert(addr, peer);
+    }
+
+    /// Changes the map played, sending it to all the connected peers
+    pub fn set_map(&mut self, map: MapData) {
+        self.broadcast(&Server::Map(map.clone()));
+        self.map = Some(map);
+    }
+
+    /// Disconnects a peer, returns `false` if it is not connected
+    ///
+    /// The peer is notified with `reason`, and disconnected once flushed.
+    pub fn kick(&mut self, addr: &SocketAddr, reason: &str) -> bool {
+        match self.peers.remove(addr) {
+            Some(peer) => {
+                peer.send(Server::Kicked {
+                    reason: reason.to_string(),
+                });
+                true
+            }
+            None => false,
         }
     }
 }
@@ -0,0 +1,306 @@
+extern crate some_platformer_server as server;
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use server::{Config, ServerHandle};
+
+/// Starts a server on an ephemeral local port
+fn start_server() -> ServerHandle {
+    start_server_with(Config::default())
+}
+
+/// Starts a server on an ephemeral local port, without the RTT pings
+/// (they would come in between the expected messages)
+fn start_server_with(config: Config) -> ServerHandle {
+    let config = Config {
+        bind: "127.0.0.1:0".parse().unwrap(),
+        ping_interval: 0,
+        ..config
+    };
+    server::start(&config).expect("failed to start server")
+}
+
+/// A blocking test client speaking the line protocol
+struct TestClient {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl TestClient {
+    /// Connects to the server, waiting for the map it sends first
+    fn connect(server: &ServerHandle) -> Self {
+        let mut client = TestClient::connect_to(server.local_addr());
+        assert!(client.recv().starts_with(r#"{"Map":"#));
+
+        client
+    }
+
+    fn connect_to(addr: SocketAddr) -> Self {
+        let stream = TcpStream::connect(addr).expect("failed to connect");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        TestClient {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+        }
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.writer, "{}\r", line).unwrap();
+    }
+
+    fn recv(&mut self) -> String {
+        let mut line = String::new();
+        self.reader
+            .read_line(&mut line)
+            .expect("no message received");
+        line.trim_end().to_string()
+    }
+
+    /// Pings the server, waiting for the pong
+    ///
+    /// Once answered, the client is known to be registered by the server.
+    fn ping(&mut self) -> String {
+        self.send(r#"{"Ping":{"secs_since_epoch":0,"nanos_since_epoch":0}}"#);
+        self.recv()
+    }
+}
+
+#[test]
+fn binds_an_ephemeral_port() {
+    let server = start_server();
+
+    assert_ne!(server.local_addr().port(), 0);
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn answers_ping_with_pong() {
+    let server = start_server();
+    let mut client = TestClient::connect(&server);
+
+    assert!(client.ping().starts_with(r#"{"Pong":"#));
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn forwards_test_message_to_other_peers() {
+    let server = start_server();
+    let mut alice = TestClient::connect(&server);
+    let mut bob = TestClient::connect(&server);
+    alice.ping();
+    bob.ping();
+
+    alice.send(r#""Test""#);
+
+    assert_eq!(bob.recv(), r#""Test""#);
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn pings_clients_regularly() {
+    let server = server::start(&Config {
+        bind: "127.0.0.1:0".parse().unwrap(),
+        ping_interval: 1,
+        ..Config::default()
+    })
+    .expect("failed to start server");
+    let mut client = TestClient::connect(&server);
+    client.ping();
+
+    assert!(client.recv().starts_with(r#"{"Ping":"#));
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn refuses_connections_after_shutdown() {
+    let server = start_server();
+    let addr = server.local_addr();
+
+    server.shutdown("test over");
+
+    assert!(TcpStream::connect(addr).is_err());
+}
+
+#[test]
+fn refuses_players_beyond_max_players() {
+    let server = start_server_with(Config {
+        max_players: 1,
+        ..Config::default()
+    });
+    let mut alice = TestClient::connect(&server);
+    alice.ping();
+
+    let mut bob = TestClient::connect_to(server.local_addr());
+
+    let mut line = String::new();
+    assert_eq!(bob.reader.read_line(&mut line).unwrap(), 0);
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn rejects_invalid_config() {
+    let config = Config {
+        tick_rate: 0,
+        ..Config::default()
+    };
+
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn refuses_to_start_with_invalid_config() {
+    let config = Config {
+        bind: "127.0.0.1:0".parse().unwrap(),
+        tick_rate: 0,
+        ..Config::default()
+    };
+
+    let err = server::start(&config)
+        .err()
+        .expect("started with an invalid config");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn notifies_clients_on_shutdown() {
+    let server = start_server();
+    let mut client = TestClient::connect(&server);
+    client.ping();
+
+    server.shutdown("maintenance");
+
+    assert_eq!(client.recv(), r#"{"Shutdown":{"reason":"maintenance"}}"#);
+
+    let mut line = String::new();
+    assert_eq!(client.reader.read_line(&mut line).unwrap(), 0);
+}
+
+/// Starts a server with the admin console enabled
+fn start_server_with_admin() -> ServerHandle {
+    start_server_with(Config {
+        admin_bind: "127.0.0.1:0".parse().unwrap(),
+        admin_password: Some("secret".into()),
+        ..Config::default()
+    })
+}
+
+fn connect_admin(server: &ServerHandle) -> TestClient {
+    TestClient::connect_to(server.admin_addr().unwrap())
+}
+
+#[test]
+fn admin_requires_authentication() {
+    let server = start_server_with_admin();
+    let mut admin = connect_admin(&server);
+
+    admin.send("players");
+
+    assert_eq!(admin.recv(), "error: authentication failed");
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn admin_lists_players_and_broadcasts() {
+    let server = start_server_with_admin();
+    let mut client = TestClient::connect(&server);
+    client.send(r#"{"Hello":{"name":"alice"}}"#);
+    client.ping();
+
+    let mut admin = connect_admin(&server);
+    admin.send("auth secret");
+    assert_eq!(admin.recv(), "authenticated");
+
+    // The name is registered by the game thread, on its next frame
+    let mut player = String::new();
+    for _ in 0..50 {
+        admin.send("players");
+        assert_eq!(admin.recv(), "1 player(s)");
+        player = admin.recv();
+        if player.contains("alice") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(player.contains("alice"));
+
+    admin.send("say hello everyone");
+    assert_eq!(admin.recv(), "announced");
+
+    assert_eq!(client.recv(), r#"{"Announcement":"hello everyone"}"#);
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn admin_kicks_players() {
+    let server = start_server_with_admin();
+    let mut client = TestClient::connect(&server);
+    let addr = client.writer.local_addr().unwrap();
+    client.ping();
+
+    let mut admin = connect_admin(&server);
+    admin.send("auth secret");
+    admin.recv();
+
+    admin.send(&format!("kick {} cheating", addr));
+    assert_eq!(admin.recv(), format!("{} kicked", addr));
+
+    assert_eq!(client.recv(), r#"{"Kicked":{"reason":"cheating"}}"#);
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn admin_steps_only_while_paused() {
+    let server = start_server_with_admin();
+    let mut admin = connect_admin(&server);
+    admin.send("auth secret");
+    admin.recv();
+
+    admin.send("step 2");
+    assert_eq!(
+        admin.recv(),
+        "error: the game is not paused, `pause` it first"
+    );
+
+    admin.send("pause");
+    assert_eq!(admin.recv(), "ok");
+    admin.send("step 2");
+    assert_eq!(admin.recv(), "ok");
+
+    server.shutdown("test over");
+}
+
+#[test]
+fn serves_metrics() {
+    let server = start_server_with(Config {
+        metrics_bind: Some("127.0.0.1:0".parse().unwrap()),
+        ..Config::default()
+    });
+    let mut client = TestClient::connect(&server);
+    client.ping();
+
+    let mut http = TcpStream::connect(server.metrics_addr().unwrap()).unwrap();
+    write!(http, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    http.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("some_platformer_connected_peers 1"));
+    assert!(response.contains(r#"some_platformer_messages_total{direction="in",kind="Ping"} 1"#));
+    assert!(response.contains("some_platformer_tick_duration_seconds_count"));
+
+    server.shutdown("test over");
+}